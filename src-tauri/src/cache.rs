@@ -0,0 +1,157 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::filesystem::get_home;
+
+// Serializes read-modify-write cycles on index.json between concurrent downloads.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CacheEntry {
+    pub url: String,
+    pub sha256: String,
+    pub file_name: String,
+    pub size: u64,
+    pub added_at: u64,
+}
+
+fn get_cache_dir() -> Option<PathBuf> {
+    let home = get_home()?;
+    Some(home.join(".stackmanager").join("cache"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn load_index(cache_dir: &Path) -> Vec<CacheEntry> {
+    fs::read_to_string(cache_dir.join("index.json"))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(cache_dir: &Path, entries: &[CacheEntry]) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    fs::write(cache_dir.join("index.json"), data).map_err(|e| e.to_string())
+}
+
+fn blob_path(cache_dir: &Path, sha256: &str) -> PathBuf {
+    cache_dir.join("blobs").join(sha256)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Returns the cached blob for `url`, if a previous download stored one.
+pub fn lookup(url: &str) -> Option<PathBuf> {
+    let cache_dir = get_cache_dir()?;
+    let _guard = INDEX_LOCK.lock().ok()?;
+    let entry = load_index(&cache_dir).into_iter().find(|e| e.url == url)?;
+    let blob = blob_path(&cache_dir, &entry.sha256);
+    if blob.exists() { Some(blob) } else { None }
+}
+
+// Path for an in-progress download; `store` moves it into the blob store.
+pub fn temp_path() -> Result<PathBuf, String> {
+    let cache_dir = get_cache_dir().ok_or("Could not find home directory")?;
+    let tmp_dir = cache_dir.join("tmp");
+    if !tmp_dir.exists() { fs::create_dir_all(&tmp_dir).map_err(|e| e.to_string())?; }
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    Ok(tmp_dir.join(format!("{}-{}.part", std::process::id(), nanos)))
+}
+
+pub fn store(url: &str, file_name: &str, downloaded: &Path, sha256: &str) -> Result<PathBuf, String> {
+    let cache_dir = get_cache_dir().ok_or("Could not find home directory")?;
+    let blobs_dir = cache_dir.join("blobs");
+    if !blobs_dir.exists() { fs::create_dir_all(&blobs_dir).map_err(|e| e.to_string())?; }
+
+    let blob = blob_path(&cache_dir, sha256);
+    if blob.exists() {
+        fs::remove_file(downloaded).ok();
+    } else {
+        fs::rename(downloaded, &blob).map_err(|e| e.to_string())?;
+    }
+
+    let size = fs::metadata(&blob).map(|m| m.len()).unwrap_or(0);
+
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Failed to lock cache index")?;
+    let mut entries = load_index(&cache_dir);
+    entries.retain(|e| e.url != url);
+    entries.push(CacheEntry {
+        url: url.to_string(),
+        sha256: sha256.to_string(),
+        file_name: file_name.to_string(),
+        size,
+        added_at: now_secs(),
+    });
+    save_index(&cache_dir, &entries)?;
+
+    Ok(blob)
+}
+
+#[tauri::command]
+pub fn list_cache() -> Result<Vec<CacheEntry>, String> {
+    let cache_dir = get_cache_dir().ok_or("Could not find home directory")?;
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Failed to lock cache index")?;
+
+    let entries = load_index(&cache_dir)
+        .into_iter()
+        .filter(|e| blob_path(&cache_dir, &e.sha256).exists())
+        .collect();
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn prune_cache(older_than_days: Option<u64>) -> Result<String, String> {
+    let cache_dir = get_cache_dir().ok_or("Could not find home directory")?;
+    if !cache_dir.exists() { return Ok("Cache is empty".to_string()); }
+
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Failed to lock cache index")?;
+    let cutoff = older_than_days.map(|days| now_secs().saturating_sub(days * 24 * 60 * 60));
+
+    let (kept, removed): (Vec<CacheEntry>, Vec<CacheEntry>) = load_index(&cache_dir)
+        .into_iter()
+        .partition(|e| match cutoff {
+            Some(c) => e.added_at >= c && blob_path(&cache_dir, &e.sha256).exists(),
+            None => false,
+        });
+
+    let mut freed: u64 = 0;
+    for entry in &removed {
+        // Several URLs can point at the same content; only drop blobs nobody references.
+        if kept.iter().any(|k| k.sha256 == entry.sha256) { continue; }
+        let blob = blob_path(&cache_dir, &entry.sha256);
+        if let Ok(meta) = fs::metadata(&blob) {
+            if fs::remove_file(&blob).is_ok() { freed += meta.len(); }
+        }
+    }
+
+    // Orphaned blobs and interrupted downloads (leave anything touched in the last hour alone)
+    let stale_before = SystemTime::now() - Duration::from_secs(60 * 60);
+    for dir in ["blobs", "tmp"] {
+        if let Ok(files) = fs::read_dir(cache_dir.join(dir)) {
+            for file in files.flatten() {
+                let name = file.file_name().to_string_lossy().to_string();
+                let referenced = dir == "blobs" && kept.iter().any(|k| k.sha256 == name);
+                let in_progress = dir == "tmp" && file.metadata()
+                    .and_then(|m| m.modified())
+                    .map(|t| t > stale_before)
+                    .unwrap_or(false);
+                if !referenced && !in_progress {
+                    if let Ok(meta) = file.metadata() {
+                        if fs::remove_file(file.path()).is_ok() { freed += meta.len(); }
+                    }
+                }
+            }
+        }
+    }
+
+    save_index(&cache_dir, &kept)?;
+
+    Ok(format!("Removed {} cached downloads ({:.1} MB freed)", removed.len(), freed as f64 / 1_048_576.0))
+}
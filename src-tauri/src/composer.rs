@@ -1,26 +1,43 @@
-use std::process::{Command, Stdio};
 use std::path::PathBuf;
-use std::env;
 use std::fs;
-use reqwest::Client;
-use futures_util::StreamExt;
-use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::io::{BufRead, BufReader};
+use serde::Serialize;
+use serde_json::Value;
 use tauri::{AppHandle, Emitter};
-use zip::ZipArchive;
+use crate::downloader::fetch_to;
+use crate::filesystem::get_home;
+use crate::php_extensions::set_extension_enabled_in;
+use crate::php_ini::IniFile;
+use crate::pipelines::{context_for, prepend_path, tool_dirs};
+use crate::scaffold::php_binary;
+use crate::store::read_projects;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ComposerOutput {
+    pub project: String,
+    // "stdout" or "stderr"
+    pub stream: String,
+    pub line: String,
+}
 
-fn get_paths() -> Option<(PathBuf, PathBuf)> {
-    #[cfg(target_os = "windows")]
-    let home = env::var("USERPROFILE").ok().map(PathBuf::from)?;
-    #[cfg(not(target_os = "windows"))]
-    let home = env::var("HOME").ok().map(PathBuf::from)?;
+#[derive(Serialize, Clone, Debug)]
+pub struct ComposerResult {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    // Parsed stdout for commands run with --format=json
+    pub json: Option<Value>,
+}
 
+fn get_paths() -> Option<(PathBuf, PathBuf)> {
+    let home = get_home()?;
     let bin_dir = home.join(".stackmanager").join("bin");
     let services_dir = home.join(".stackmanager").join("services");
 
     Some((bin_dir, services_dir))
 }
 
-fn ensure_php_extensions(php_dir: &PathBuf) -> Result<(), String> {
+pub fn ensure_php_extensions(php_dir: &PathBuf) -> Result<(), String> {
     let ini_path = php_dir.join("php.ini");
     let dev_ini = php_dir.join("php.ini-development");
     
@@ -32,38 +49,18 @@ fn ensure_php_extensions(php_dir: &PathBuf) -> Result<(), String> {
         }
     }
 
-    let content = fs::read_to_string(&ini_path).map_err(|e| e.to_string())?;
-    let mut new_lines = Vec::new();
-    let mut modified = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-        
-        if trimmed.contains(";extension_dir = \"ext\"") {
-             new_lines.push("extension_dir = \"ext\"".to_string());
-             modified = true;
-             continue;
-        }
+    let mut ini = IniFile::load(&ini_path)?;
 
-        if trimmed.starts_with(";") && (
-           trimmed.contains("extension=openssl") || 
-           trimmed.contains("extension=mbstring") || 
-           trimmed.contains("extension=curl") || 
-           trimmed.contains("extension=fileinfo") || 
-           trimmed.contains("extension=pdo_mysql") || 
-           trimmed.contains("extension=mysqli") ||
-           trimmed.contains("extension=zip") 
-        ) {
-            new_lines.push(trimmed[1..].to_string());
-            modified = true;
-            continue;
-        }
+    if ini.get("extension_dir").is_none() {
+        ini.set("extension_dir", "ext");
+    }
 
-        new_lines.push(line.to_string());
+    for ext in ["openssl", "mbstring", "curl", "fileinfo", "pdo_mysql", "mysqli", "zip"] {
+        set_extension_enabled_in(&mut ini, ext, true, false);
     }
 
-    if modified {
-        fs::write(&ini_path, new_lines.join("\n")).map_err(|e| e.to_string())?;
+    if ini.is_modified() {
+        ini.save(&ini_path)?;
         println!("PHP.ini updated with required extensions.");
     }
 
@@ -84,192 +81,143 @@ pub async fn init_composer() -> Result<String, String> {
         return Ok("Composer ready.".to_string());
     }
 
-    let url = "https://getcomposer.org/download/latest-stable/composer.phar";
-    let client = Client::new();
-    let res = client.get(url).send().await.map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() {
-        return Err(format!("Failed to download Composer: {}", res.status()));
-    }
-
-    let mut stream = res.bytes_stream();
-    let mut file = fs::File::create(&composer_path).map_err(|e| e.to_string())?;
-
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
-    }
+    fetch_to("https://getcomposer.org/download/latest-stable/composer.phar", &composer_path).await
+        .map_err(|e| format!("Failed to download Composer: {}", e))?;
 
     Ok("Composer downloaded successfully!".to_string())
 }
 
-#[tauri::command]
-pub async fn create_laravel_project(
-    app: AppHandle, 
-    project_name: String, 
-    parent_folder: String
-) -> Result<String, String> {
+// Composer 1 lives next to the default 2.x phar so both can be kept around
+fn composer_phar_for(major: u8) -> Result<(PathBuf, &'static str), String> {
     let (bin_dir, _) = get_paths().ok_or("Home dir not found")?;
-    let composer_phar = bin_dir.join("composer.phar");
-    let php_exe = bin_dir.join("php").join("php.exe");
+    match major {
+        1 => Ok((bin_dir.join("composer1.phar"), "https://getcomposer.org/download/latest-1.x/composer.phar")),
+        2 => Ok((bin_dir.join("composer.phar"), "https://getcomposer.org/download/latest-stable/composer.phar")),
+        other => Err(format!("Unsupported Composer version: {}", other)),
+    }
+}
 
-    ensure_php_extensions(&bin_dir.join("php"))?;
+async fn ensure_composer(major: u8) -> Result<PathBuf, String> {
+    let (phar, url) = composer_phar_for(major)?;
+    if phar.exists() {
+        return Ok(phar);
+    }
 
-    if !composer_phar.exists() { return Err("Composer missing".to_string()); }
+    fetch_to(url, &phar).await
+        .map_err(|e| format!("Failed to download Composer {}: {}", major, e))?;
+    Ok(phar)
+}
 
-    let mut cmd = if cfg!(target_os = "windows") {
-        let mut c = Command::new("cmd");
-        c.args(&["/C", &php_exe.to_string_lossy(), &composer_phar.to_string_lossy(), "create-project", "laravel/laravel", &project_name, "--prefer-dist"]);
-        c
-    } else {
-        let mut c = Command::new(&php_exe);
-        c.args(&[&composer_phar.to_string_lossy(), "create-project", "laravel/laravel", &project_name, "--prefer-dist"]);
-        c
-    };
+// Explicit choice, then the project's "composerVersion", then Composer 2
+fn composer_major(project_path: &str, requested: Option<u8>) -> u8 {
+    let trimmed = |p: &str| p.trim_end_matches(['/', '\\']).to_string();
+    requested.or_else(|| {
+        read_projects().ok()?.into_iter()
+            .find(|p| p.get("path").and_then(Value::as_str).map(trimmed) == Some(trimmed(project_path)))?
+            .get("composerVersion")
+            .and_then(|v| v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+            .map(|v| v as u8)
+    }).unwrap_or(2)
+}
 
-    cmd.current_dir(&parent_folder)
-       .stdout(Stdio::piped())
-       .stderr(Stdio::piped());
+fn wants_json(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--format=json")
+        || args.windows(2).any(|w| w[0] == "--format" && w[1] == "json")
+}
 
+// Streams both pipes as "composer-output" events; stdout is also kept when `capture` is set
+async fn run_composer_command(app: &AppHandle, project: &str, mut cmd: Command, capture: bool) -> Result<(Option<i32>, String), String> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
     let mut child = cmd.spawn().map_err(|e| format!("Failed to start composer: {}", e))?;
-
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
 
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines() {
-            if let Ok(l) = line {
-                let _ = app_handle.emit("composer-progress", l); 
-            }
+    let emit = |app: AppHandle, project: String, stream: &'static str| {
+        move |line: String| {
+            let _ = app.emit("composer-output", ComposerOutput { project: project.clone(), stream: stream.to_string(), line });
         }
-    });
+    };
 
-    let app_handle_err = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines() {
-            if let Ok(l) = line {
-                let _ = app_handle_err.emit("composer-progress", l);
+    let on_out = emit(app.clone(), project.to_string(), "stdout");
+    let out = std::thread::spawn(move || {
+        let mut captured = String::new();
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if capture {
+                captured.push_str(&line);
+                captured.push('\n');
+            } else {
+                on_out(line);
             }
         }
+        captured
+    });
+    let on_err = emit(app.clone(), project.to_string(), "stderr");
+    let err = std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) { on_err(line); }
     });
 
-    let status = tauri::async_runtime::spawn_blocking(move || {
-        child.wait()
-    }).await.map_err(|e| e.to_string())?
-      .map_err(|e| e.to_string())?;
-
-    if status.success() {
-        let path = std::path::Path::new(&parent_folder).join(&project_name);
-        Ok(path.to_string_lossy().to_string())
-    } else {
-        Err("Composer exited with error code.".to_string())
-    }
+    let status = tauri::async_runtime::spawn_blocking(move || child.wait())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let captured = out.join().unwrap_or_default();
+    let _ = err.join();
+    Ok((status.code(), captured))
 }
 
+// Runs composer in a project with the project's PHP version, e.g. ["outdated", "--format=json"]
 #[tauri::command]
-pub async fn create_wordpress_project(
+pub async fn run_composer(
     app: AppHandle,
-    project_name: String,
-    parent_folder: String
-) -> Result<String, String> {
-    let target_dir = PathBuf::from(&parent_folder).join(&project_name);
-    if target_dir.exists() {
-        return Err("Target directory already exists".to_string());
-    }
-
-    let _ = app.emit("composer-progress", format!("Downloading WordPress to {}...", project_name));
-    
-    let url = "https://wordpress.org/latest.zip";
-    let client = Client::new();
-    let res = client.get(url).send().await.map_err(|e| e.to_string())?;
-    
-    if !res.status().is_success() {
-        return Err(format!("Download failed: {}", res.status()));
+    project_path: String,
+    args: Vec<String>,
+    composer_version: Option<u8>,
+) -> Result<ComposerResult, String> {
+    let (ctx, _) = context_for(&project_path);
+    if !ctx.path.join("composer.json").exists() && args.first().map(String::as_str) != Some("init") {
+        return Err("No composer.json in this project".to_string());
     }
 
-    let temp_zip = PathBuf::from(&parent_folder).join("wp_latest_temp.zip");
-    let mut file = fs::File::create(&temp_zip).map_err(|e| e.to_string())?;
-    let mut stream = res.bytes_stream();
-    
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
+    let phar = ensure_composer(composer_major(&project_path, composer_version)).await?;
+    let php = php_binary(ctx.php_version.as_deref())?;
+    if let Some(php_dir) = php.parent().filter(|d| d.join("php.ini").exists() || d.join("php.ini-development").exists()) {
+        ensure_php_extensions(&php_dir.to_path_buf())?;
     }
 
-    let _ = app.emit("composer-progress", "Extracting files...");
-
-    let file = fs::File::open(&temp_zip).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let capture = wants_json(&args);
+    let mut cmd = Command::new(&php);
+    // Global options go first; after the user's args they would reach `run-script`/`exec` targets instead
+    cmd.arg(&phar).args(["--no-interaction", "--no-ansi"]).args(&args).current_dir(&ctx.path);
+    prepend_path(&mut cmd, &tool_dirs(&ctx)?);
 
-    let wp_default_dir = PathBuf::from(&parent_folder).join("wordpress");
-    if wp_default_dir.exists() {
-        fs::remove_file(&temp_zip).ok();
-        return Err("A 'wordpress' folder already exists in the destination. Please rename or remove it.".to_string());
-    }
-
-    archive.extract(&parent_folder).map_err(|e| e.to_string())?;
-    
-    drop(archive); 
-    fs::remove_file(&temp_zip).ok();
-
-    fs::rename(&wp_default_dir, &target_dir).map_err(|e| format!("Failed to rename folder: {}", e))?;
-
-    let _ = app.emit("composer-progress", "WordPress setup complete!");
-    
-    Ok(target_dir.to_string_lossy().to_string())
+    let (exit_code, stdout) = run_composer_command(&app, &project_path, cmd, capture).await?;
+    let json = if capture { serde_json::from_str(&stdout).ok() } else { None };
+    Ok(ComposerResult { success: exit_code == Some(0), exit_code, json })
 }
 
+// Updates the managed phar; `channel` is "stable", "preview" or "snapshot"
 #[tauri::command]
-pub async fn create_symfony_project(
-    app: AppHandle,
-    project_name: String,
-    parent_folder: String
-) -> Result<String, String> {
-    let (bin_dir, _) = get_paths().ok_or("Home dir not found")?;
-    let composer_phar = bin_dir.join("composer.phar");
-    let php_exe = bin_dir.join("php").join("php.exe");
-
-    ensure_php_extensions(&bin_dir.join("php"))?;
-
-    if !composer_phar.exists() { return Err("Composer missing".to_string()); }
-
-    let mut cmd = if cfg!(target_os = "windows") {
-        let mut c = Command::new("cmd");
-        c.args(&["/C", &php_exe.to_string_lossy(), &composer_phar.to_string_lossy(), "create-project", "symfony/skeleton", &project_name, "--prefer-dist"]);
-        c
-    } else {
-        let mut c = Command::new(&php_exe);
-        c.args(&[&composer_phar.to_string_lossy(), "create-project", "symfony/skeleton", &project_name, "--prefer-dist"]);
-        c
+pub async fn composer_self_update(app: AppHandle, composer_version: Option<u8>, channel: Option<String>) -> Result<String, String> {
+    let major = composer_version.unwrap_or(2);
+    let phar = ensure_composer(major).await?;
+
+    // One channel flag only; --1/--2 keep a phar on its major line
+    let flag = match (major, channel.as_deref()) {
+        (1, None | Some("stable")) => "--1",
+        (1, Some(_)) => return Err("Composer 1 only has a stable channel".to_string()),
+        (_, None | Some("stable")) => "--2",
+        (_, Some("preview")) => "--preview",
+        (_, Some("snapshot")) => "--snapshot",
+        (_, Some(other)) => return Err(format!("Unknown update channel: {}", other)),
     };
 
-    cmd.current_dir(&parent_folder).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut cmd = Command::new(php_binary(None)?);
+    cmd.arg(&phar).args(["self-update", flag, "--no-interaction", "--no-ansi"]);
 
-    let mut child = cmd.spawn().map_err(|e| format!("Failed to start composer: {}", e))?;
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines() { if let Ok(l) = line { let _ = app_handle.emit("composer-progress", l); } }
-    });
-
-    let app_handle_err = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines() { if let Ok(l) = line { let _ = app_handle_err.emit("composer-progress", l); } }
-    });
-
-    let status = tauri::async_runtime::spawn_blocking(move || child.wait()).await.map_err(|e| e.to_string())?.map_err(|e| e.to_string())?;
-
-    if status.success() {
-        let path = std::path::Path::new(&parent_folder).join(&project_name);
-        Ok(path.to_string_lossy().to_string())
+    let (exit_code, _) = run_composer_command(&app, "", cmd, false).await?;
+    if exit_code == Some(0) {
+        Ok(format!("Composer {} updated", major))
     } else {
-        Err("Composer exited with error code.".to_string())
+        Err("Composer self-update failed".to_string())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,33 @@
+use keyring::Entry;
+use rand::{distributions::Alphanumeric, Rng};
+
+const SERVICE: &str = "StackManager";
+
+pub fn generate_password() -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(24).map(char::from).collect()
+}
+
+pub fn instance_account(instance: &str) -> String {
+    format!("db-instance/{}", instance)
+}
+
+pub fn project_db_account(instance: &str, database: &str) -> String {
+    format!("project-db/{}/{}", instance, database)
+}
+
+// No plain-text fallback: callers store the secret before relying on it, so a failure here changes nothing
+pub fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    Entry::new(SERVICE, account)
+        .and_then(|entry| entry.set_password(secret))
+        .map_err(|e| format!("Could not save the password in the OS keyring: {}", e))
+}
+
+pub fn get_secret(account: &str) -> Option<String> {
+    Entry::new(SERVICE, account).and_then(|entry| entry.get_password()).ok()
+}
+
+pub fn delete_secret(account: &str) {
+    if let Ok(entry) = Entry::new(SERVICE, account) {
+        let _ = entry.delete_credential();
+    }
+}
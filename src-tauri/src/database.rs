@@ -1,150 +1,387 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::env;
+use crate::db_backup::snapshot_instance;
+use mysql_async::prelude::*;
+use crate::db_client::{mysql_connect, mysql_literal, pg_connect, pg_ident, pg_literal, pg_password_verifier, resolve_instance};
+use crate::credentials::generate_password;
+use crate::db_instances::{all_instances, config_path, engine_and_version, find_tool, instance_dir, load_instance, next_free_port, save_instance, set_instance_password, DbInstance};
+use crate::filesystem::get_home;
+
+fn mysql_config(instance: &DbInstance, base_dir: &Path) -> String {
+    let mut mysqld = vec![
+        "[mysqld]".to_string(),
+        format!("port={}", instance.port),
+        format!("basedir={}", config_path(base_dir)),
+        format!("datadir={}", instance.data_dir),
+        "bind-address=127.0.0.1".to_string(),
+    ];
+    let mut client = vec!["[client]".to_string(), format!("port={}", instance.port)];
 
-fn get_home() -> Option<PathBuf> {
-    #[cfg(target_os = "windows")]
-    return env::var("USERPROFILE").ok().map(PathBuf::from);
     #[cfg(not(target_os = "windows"))]
-    return env::var("HOME").ok().map(PathBuf::from);
+    {
+        let socket = Path::new(&instance.config_file).with_file_name("mysql.sock");
+        mysqld.push(format!("socket={}", config_path(&socket)));
+        client.push(format!("socket={}", config_path(&socket)));
+    }
+
+    mysqld.push(String::new());
+    mysqld.extend(client);
+    mysqld.join("\n") + "\n"
 }
 
-fn find_bin_path(base_dir: &PathBuf, exe_name: &str) -> Option<PathBuf> {
-    let direct = base_dir.join("bin").join(exe_name);
-    if direct.exists() { return Some(direct); }
+fn service_dir(version_folder: &str) -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("services").join(version_folder))
+}
 
-    if let Ok(entries) = fs::read_dir(base_dir) {
-        for entry in entries.flatten() {
-            if let Ok(ft) = entry.file_type() {
-                if ft.is_dir() {
-                    let nested = entry.path().join("bin").join(exe_name);
-                    if nested.exists() { return Some(nested); }
-                }
-            }
-        }
-    }
-    None
+// The server binary and the install root it expects as basedir
+fn mysql_layout(instance: &DbInstance) -> Result<(PathBuf, PathBuf), String> {
+    let service_dir = service_dir(&instance.version_folder)?;
+    let mysqld = find_tool(&service_dir, &["mariadbd", "mysqld"])
+        .ok_or(format!("Could not find mysqld in {}", instance.version_folder))?;
+    let base_dir = mysqld.parent().and_then(|p| p.parent()).unwrap_or(&service_dir).to_path_buf();
+    Ok((mysqld, base_dir))
 }
 
-#[tauri::command]
-pub fn init_mysql(version_folder: String) -> Result<String, String> {
-    let home = get_home().ok_or("Home not found")?;
-    let base = home.join(".stackmanager");
-    let service_dir = base.join("services").join(&version_folder);
+pub fn write_mysql_config(instance: &DbInstance) -> Result<(), String> {
+    let (_, base_dir) = mysql_layout(instance)?;
+    fs::write(&instance.config_file, mysql_config(instance, &base_dir)).map_err(|e| e.to_string())
+}
 
-    let data_dir = base.join("data").join("mysql");
-    
-    if data_dir.join("mysql").exists() {
-        return Ok("MariaDB already initialized".to_string());
-    }
+pub fn initialize_mysql_datadir(instance: &DbInstance) -> Result<(), String> {
+    let service_dir = service_dir(&instance.version_folder)?;
+    let (mysqld, base_dir) = mysql_layout(instance)?;
+    let data_dir = PathBuf::from(&instance.data_dir);
 
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
-    }
+    let install_db = if instance.engine == "mariadb" {
+        find_tool(&service_dir, &["mariadb-install-db", "mysql_install_db"])
+    } else {
+        None
+    };
 
-    let install_db_exe = find_bin_path(&service_dir, "mysql_install_db.exe")
-        .ok_or(format!("Could not find mysql_install_db.exe in {}", version_folder))?;
+    let mut command = match &install_db {
+        Some(tool) => {
+            println!("Initializing MariaDB with: {:?}", tool);
+            fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+            let mut c = Command::new(tool);
+            if cfg!(target_os = "windows") {
+                c.arg(format!("--datadir={}", data_dir.to_string_lossy()));
+            } else {
+                c.arg("--no-defaults")
+                    .arg(format!("--basedir={}", base_dir.to_string_lossy()))
+                    .arg(format!("--datadir={}", data_dir.to_string_lossy()))
+                    .arg("--auth-root-authentication-method=normal");
+            }
+            c
+        }
+        None => {
+            // MySQL 5.7+ and MariaDB builds without the install script; the data dir must not exist yet
+            println!("Initializing with: {:?} --initialize-insecure", mysqld);
+            if data_dir.exists() && fs::read_dir(&data_dir).map(|mut d| d.next().is_none()).unwrap_or(false) {
+                fs::remove_dir(&data_dir).map_err(|e| e.to_string())?;
+            }
+            let mut c = Command::new(&mysqld);
+            c.arg("--no-defaults")
+                .arg("--initialize-insecure")
+                .arg(format!("--basedir={}", base_dir.to_string_lossy()))
+                .arg(format!("--datadir={}", data_dir.to_string_lossy()));
+            c
+        }
+    };
 
-    println!("Initializing MariaDB with: {:?}", install_db_exe);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
 
-    let output = Command::new(install_db_exe)
-        .arg(format!("--datadir={}", data_dir.to_string_lossy()))
-        .output()
-        .map_err(|e| format!("Failed to run init: {}", e))?;
+    let output = command.output().map_err(|e| format!("Failed to run init: {}", e))?;
 
     if !output.status.success() {
         let err = String::from_utf8_lossy(&output.stderr);
         let out = String::from_utf8_lossy(&output.stdout);
         return Err(format!("MariaDB Init Failed: {} {}", err, out));
     }
+    Ok(())
+}
 
-    Ok("MariaDB Initialized Successfully".to_string())
+// The server that last ran mysql_upgrade records itself, e.g. "10.6.16-MariaDB"
+fn data_dir_version(data_dir: &Path) -> Option<(String, String)> {
+    let info = fs::read_to_string(data_dir.join("mysql_upgrade_info")).ok()?;
+    let info = info.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    let engine = if info.to_lowercase().contains("mariadb") { "mariadb" } else { "mysql" };
+    let major: Vec<&str> = info.split(['-', '.']).take(2).collect();
+    if major.len() < 2 || major.iter().any(|p| p.parse::<u32>().is_err()) { return None; }
+    Some((engine.to_string(), major.join(".")))
+}
+
+// Instances created before named instances existed all shared data/mysql
+fn adopt_legacy_mysql(base: &Path, engine: &str, version_folder: &str) -> Option<DbInstance> {
+    let legacy = base.join("data").join("mysql");
+    if !legacy.join("mysql").exists() || load_instance("default").is_ok() { return None; }
+
+    // Without a recorded version the data is never matched to a server, so it can't be started by the wrong one
+    let (engine, version) = data_dir_version(&legacy).unwrap_or((engine.to_string(), "unknown".to_string()));
+    let dir = instance_dir("default").ok()?;
+    let config_name = if cfg!(target_os = "windows") { "my.ini" } else { "my.cnf" };
+    let instance = DbInstance {
+        name: "default".to_string(),
+        engine,
+        version_folder: version_folder.to_string(),
+        version,
+        port: 3306,
+        data_dir: config_path(&legacy),
+        config_file: dir.join(config_name).to_string_lossy().to_string(),
+        user: "root".to_string(),
+        password: String::new(),
+    };
+    save_instance(&instance).ok()?;
+    println!("Adopted legacy MySQL data directory as instance 'default'");
+    Some(instance)
 }
 
 #[tauri::command]
-pub fn change_mariadb_password(bin_path: String, old_pass: String, new_pass: String) -> Result<String, String> {
-    let bin_dir = PathBuf::from(&bin_path);
-    let mysqladmin = if cfg!(target_os = "windows") {
-        bin_dir.join("mysqladmin.exe")
-    } else {
-        bin_dir.join("mysqladmin")
+pub fn init_mysql(version_folder: String, instance: Option<String>, port: Option<u16>) -> Result<DbInstance, String> {
+    let home = get_home().ok_or("Home not found")?;
+    let base = home.join(".stackmanager");
+
+    let (engine, version) = engine_and_version(&version_folder)
+        .ok_or(format!("{} is not a MariaDB/MySQL build", version_folder))?;
+    if engine == "postgresql" {
+        return Err(format!("{} is not a MariaDB/MySQL build", version_folder));
+    }
+
+    let name = match instance {
+        Some(n) => n,
+        None => match load_instance("default").ok().or_else(|| adopt_legacy_mysql(&base, &engine, &version_folder)) {
+            Some(existing) if existing.engine == engine && existing.version == version => existing.name,
+            _ => format!("{}-{}", engine, version),
+        },
     };
+    let dir = instance_dir(&name)?;
+
+    let mut inst = match load_instance(&name) {
+        Ok(existing) => {
+            // Older servers cannot read data files written by a newer major version
+            if existing.version == "unknown" {
+                return Err(format!(
+                    "Instance '{}' holds data from an unknown {} version. Start it with the server that created it and run mysql_upgrade, or use another instance.",
+                    name, existing.engine
+                ));
+            }
+            if existing.engine != engine || existing.version != version {
+                return Err(format!(
+                    "Instance '{}' holds {} {} data and cannot be started with {}. Use another instance or upgrade it.",
+                    name, existing.engine, existing.version, version_folder
+                ));
+            }
+            existing
+        }
+        Err(_) => {
+            let config_name = if cfg!(target_os = "windows") { "my.ini" } else { "my.cnf" };
+            DbInstance {
+                name: name.clone(),
+                engine: engine.clone(),
+                version_folder: version_folder.clone(),
+                version: version.clone(),
+                port: match port {
+                    Some(p) => p,
+                    None => next_free_port(&engine)?,
+                },
+                data_dir: config_path(&dir.join("data")),
+                config_file: dir.join(config_name).to_string_lossy().to_string(),
+                user: "root".to_string(),
+                password: String::new(),
+            }
+        }
+    };
+    inst.version_folder = version_folder.clone();
+    if let Some(p) = port { inst.port = p; }
 
-    if !mysqladmin.exists() {
-        return Err(format!("mysqladmin not found at {:?}", mysqladmin));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    write_mysql_config(&inst)?;
+    save_instance(&inst)?;
+
+    if !PathBuf::from(&inst.data_dir).join("mysql").exists() {
+        initialize_mysql_datadir(&inst)?;
+        println!("Initialized {} instance '{}' in {}", engine, inst.name, inst.data_dir);
     }
+    Ok(inst)
+}
 
-    let mut args = vec![
-        "-u".to_string(),
-        "root".to_string(),
-    ];
+// Sets the password of the instance's admin user on every host it is defined for.
+// Connecting natively keeps both passwords out of the process list.
+pub async fn set_mysql_password(inst: &DbInstance, new_pass: &str) -> Result<(), String> {
+    let mut conn = mysql_connect(inst, None).await?;
 
+    let hosts: Vec<String> = conn.exec("SELECT Host FROM mysql.user WHERE User = ?", (inst.user.clone(),)).await
+        .map_err(|e| format!("Error: {}", e))?;
+    for host in hosts {
+        let sql = format!("ALTER USER {}@{} IDENTIFIED BY {}", mysql_literal(&inst.user), mysql_literal(&host), mysql_literal(new_pass));
+        conn.query_drop(sql).await.map_err(|e| format!("Error: {}", e))?;
+    }
+    conn.query_drop("FLUSH PRIVILEGES").await.map_err(|e| format!("Error: {}", e))?;
+    let _ = conn.disconnect().await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn change_mariadb_password(old_pass: String, new_pass: String, instance: Option<String>) -> Result<String, String> {
+    let mut inst = resolve_instance("mysql", instance.as_deref())?;
     if !old_pass.is_empty() {
-        args.push(format!("-p{}", old_pass));
+        inst.password = old_pass;
     }
 
-    args.push("password".to_string());
-    args.push(new_pass);
+    snapshot_instance(&inst, "password-change").await?;
 
-    let mut command = Command::new(mysqladmin);
-    command.args(&args);
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        command.creation_flags(CREATE_NO_WINDOW);
+    // Stored first, so a keyring failure leaves the server password unchanged
+    set_instance_password(&inst.name, &new_pass)?;
+    if let Err(e) = set_mysql_password(&inst, &new_pass).await {
+        let _ = set_instance_password(&inst.name, &inst.password);
+        return Err(if e.contains("Access denied") { "Access denied. Current password incorrect.".to_string() } else { e });
     }
+    Ok("Password updated successfully".to_string())
+}
 
-    let output = command.output().map_err(|e| format!("Failed to run mysqladmin: {}", e))?;
+fn pg_major(instance: &DbInstance) -> u32 {
+    instance.version.split('.').next().and_then(|v| v.parse().ok()).unwrap_or(0)
+}
 
-    if output.status.success() {
-        Ok("Password updated successfully".to_string())
-    } else {
-        let err = String::from_utf8_lossy(&output.stderr);
-        if err.contains("Access denied") {
-            return Err("Access denied. Current password incorrect.".to_string());
-        }
-        Err(format!("Error: {}", err))
+// scram-sha-256 arrived in PostgreSQL 10
+fn pg_auth_method(instance: &DbInstance) -> &'static str {
+    if pg_major(instance) >= 10 { "scram-sha-256" } else { "md5" }
+}
+
+// Settings StackManager owns live in their own file so postgresql.conf stays as initdb wrote it
+pub fn write_postgres_config(instance: &DbInstance) -> Result<(), String> {
+    let data_dir = PathBuf::from(&instance.data_dir);
+
+    let mut settings = vec![
+        format!("port = {}", instance.port),
+        "listen_addresses = 'localhost'".to_string(),
+        format!("password_encryption = '{}'", pg_auth_method(instance)),
+    ];
+    // Tarball builds default to a socket directory that usually doesn't exist or isn't writable
+    #[cfg(not(target_os = "windows"))]
+    if let Some(dir) = Path::new(&instance.config_file).parent() {
+        settings.push(format!("unix_socket_directories = '{}'", config_path(dir)));
+    }
+    fs::write(&instance.config_file, settings.join("\n") + "\n").map_err(|e| e.to_string())?;
+
+    let main_conf = data_dir.join("postgresql.conf");
+    let content = fs::read_to_string(&main_conf).unwrap_or_default();
+    let include = format!("include_if_exists = '{}'", instance.config_file.replace('\\', "/"));
+    if !content.lines().any(|l| l.trim() == include) {
+        let mut content = content;
+        if !content.is_empty() && !content.ends_with('\n') { content.push('\n'); }
+        content.push_str(&include);
+        content.push('\n');
+        fs::write(&main_conf, content).map_err(|e| e.to_string())?;
     }
+    Ok(())
 }
 
-#[tauri::command]
-pub fn init_postgresql(version_folder: String) -> Result<String, String> {
-    let home = get_home().ok_or("Home not found")?;
-    let base = home.join(".stackmanager");
+// Password auth for every connection, local sockets and loopback TCP only
+fn write_pg_hba(instance: &DbInstance) -> Result<(), String> {
+    let method = pg_auth_method(instance);
+    let mut lines = vec![
+        "# Generated by StackManager".to_string(),
+        "# TYPE  DATABASE        USER            ADDRESS                 METHOD".to_string(),
+    ];
+    if !cfg!(target_os = "windows") {
+        lines.push(format!("local   all             all                                     {}", method));
+    }
+    lines.push(format!("host    all             all             127.0.0.1/32            {}", method));
+    lines.push(format!("host    all             all             ::1/128                 {}", method));
+    if !cfg!(target_os = "windows") {
+        lines.push(format!("local   replication     all                                     {}", method));
+    }
+    lines.push(format!("host    replication     all             127.0.0.1/32            {}", method));
+    lines.push(format!("host    replication     all             ::1/128                 {}", method));
 
-    let service_dir = base.join("services").join(&version_folder).join("pgsql");
-    let data_dir = base.join("data").join("postgresql");
+    let path = PathBuf::from(&instance.data_dir).join("pg_hba.conf");
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn uses_trust_auth(instance: &DbInstance) -> bool {
+    fs::read_to_string(PathBuf::from(&instance.data_dir).join("pg_hba.conf"))
+        .map(|content| content.lines()
+            .map(|l| l.split('#').next().unwrap_or("").trim())
+            .any(|l| l.split_whitespace().last() == Some("trust")))
+        .unwrap_or(false)
+}
+
+// The shared data/postgresql directory from before named instances, if its major version matches
+fn adopt_legacy_postgres(base: &Path, version: &str, version_folder: &str) -> Option<DbInstance> {
+    let legacy = base.join("data").join("postgresql");
+    let legacy_version = fs::read_to_string(legacy.join("PG_VERSION")).ok()?;
+    if legacy_version.trim() != version { return None; }
+
+    let name = format!("postgresql-{}", version);
+    if load_instance(&name).is_ok() { return None; }
+
+    let instance = DbInstance {
+        name: name.clone(),
+        engine: "postgresql".to_string(),
+        version_folder: version_folder.to_string(),
+        version: version.to_string(),
+        port: 5432,
+        data_dir: config_path(&legacy),
+        config_file: instance_dir(&name).ok()?.join("stackmanager.conf").to_string_lossy().to_string(),
+        user: "postgres".to_string(),
+        password: String::new(),
+    };
+    save_instance(&instance).ok()?;
+    println!("Adopted legacy PostgreSQL data directory as instance '{}'", name);
+    Some(instance)
+}
 
+pub fn initialize_pg_datadir(instance: &DbInstance, password: &str) -> Result<(), String> {
+    let service_dir = service_dir(&instance.version_folder)?;
+    let data_dir = PathBuf::from(&instance.data_dir);
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
     }
 
-    if data_dir.join("PG_VERSION").exists() {
-        return Ok("PostgreSQL already initialized".to_string());
-    }
-
-    let initdb_exe = service_dir.join("bin").join("initdb.exe");
-    if !initdb_exe.exists() {
-        return Err(format!("Could not find initdb.exe at {:?}", initdb_exe));
-    }
+    let initdb_exe = find_tool(&service_dir, &["initdb"])
+        .ok_or(format!("Could not find initdb in {}", instance.version_folder))?;
 
     println!("Initializing PostgreSQL...");
 
-    let output = Command::new(initdb_exe)
+    // initdb reads the superuser password from a file so it never shows up in the process list
+    let pwfile = Path::new(&instance.config_file).with_file_name(".pwfile");
+    fs::write(&pwfile, password).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&pwfile, fs::Permissions::from_mode(0o600));
+    }
+
+    let mut command = Command::new(initdb_exe);
+    command
         .arg("-D")
         .arg(&data_dir)
         .arg("-U")
-        .arg("postgres")
+        .arg(&instance.user)
         .arg("-E")
         .arg("UTF8")
         .arg("--no-locale")
         .arg("-A")
-        .arg("trust") 
-        .output()
-        .map_err(|e| format!("Failed to run initdb: {}", e))?;
+        .arg(pg_auth_method(instance))
+        .arg(format!("--pwfile={}", pwfile.to_string_lossy()));
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output();
+    let _ = fs::remove_file(&pwfile);
+    let output = output.map_err(|e| format!("Failed to run initdb: {}", e))?;
 
     if !output.status.success() {
         let err = String::from_utf8_lossy(&output.stderr);
@@ -152,36 +389,138 @@ pub fn init_postgresql(version_folder: String) -> Result<String, String> {
         return Err(format!("PostgreSQL Init Failed: {}\n{}", err, out));
     }
 
-    Ok("PostgreSQL Initialized Successfully".to_string())
+    write_pg_hba(instance)?;
+    write_postgres_config(instance)
 }
 
 #[tauri::command]
-pub fn change_postgres_password(bin_path: String, new_pass: String) -> Result<String, String> {
-    let bin_dir = PathBuf::from(&bin_path);
-    let psql_exe = bin_dir.join("psql.exe");
+pub fn init_postgresql(version_folder: String, instance: Option<String>, port: Option<u16>) -> Result<DbInstance, String> {
+    let home = get_home().ok_or("Home not found")?;
+    let base = home.join(".stackmanager");
+
+    let (engine, version) = engine_and_version(&version_folder)
+        .ok_or(format!("Could not determine the PostgreSQL version of {}", version_folder))?;
+    if engine != "postgresql" {
+        return Err(format!("{} is not a PostgreSQL build", version_folder));
+    }
 
-    if !psql_exe.exists() {
-        return Err("Could not find psql.exe".to_string());
+    let name = match instance {
+        Some(n) => n,
+        None => {
+            adopt_legacy_postgres(&base, &version, &version_folder);
+            format!("postgresql-{}", version)
+        }
+    };
+    let dir = instance_dir(&name)?;
+
+    let mut inst = match load_instance(&name) {
+        Ok(existing) => {
+            if existing.engine != engine || existing.version != version {
+                return Err(format!(
+                    "Instance '{}' holds {} {} data and cannot be started with {}. Use another instance or upgrade it.",
+                    name, existing.engine, existing.version, version_folder
+                ));
+            }
+            existing
+        }
+        Err(_) => DbInstance {
+            name: name.clone(),
+            engine: engine.clone(),
+            version_folder: version_folder.clone(),
+            version: version.clone(),
+            port: match port {
+                Some(p) => p,
+                None => next_free_port(&engine)?,
+            },
+            data_dir: config_path(&dir.join("data")),
+            config_file: dir.join("stackmanager.conf").to_string_lossy().to_string(),
+            user: "postgres".to_string(),
+            password: String::new(),
+        },
+    };
+    inst.version_folder = version_folder.clone();
+    if let Some(p) = port { inst.port = p; }
+
+    let data_dir = PathBuf::from(&inst.data_dir);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    save_instance(&inst)?;
+
+    if data_dir.join("PG_VERSION").exists() {
+        write_postgres_config(&inst)?;
+        return Ok(inst);
     }
 
-    let sql = format!("ALTER USER postgres WITH PASSWORD '{}';", new_pass);
+    let password = generate_password();
+    set_instance_password(&inst.name, &password)?;
+    initialize_pg_datadir(&inst, &password)?;
+    inst.password = password;
 
-    let mut command = Command::new(&psql_exe);
-    command.args(&["-U", "postgres", "-d", "postgres", "-c", &sql]);
+    println!("Initialized PostgreSQL instance '{}' in {}", inst.name, inst.data_dir);
+    Ok(inst)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        command.creation_flags(CREATE_NO_WINDOW);
+#[tauri::command]
+pub async fn change_postgres_password(new_pass: String, instance: Option<String>) -> Result<String, String> {
+    let inst = resolve_instance("postgresql", instance.as_deref())?;
+    snapshot_instance(&inst, "password-change").await?;
+
+    let client = pg_connect(&inst, "postgres").await?;
+    set_instance_password(&inst.name, &new_pass)?;
+    let sql = format!("ALTER ROLE {} WITH PASSWORD {}", pg_ident(&inst.user), pg_literal(&pg_password_verifier(&new_pass)));
+    if let Err(e) = client.batch_execute(&sql).await {
+        let _ = set_instance_password(&inst.name, &inst.password);
+        return Err(format!("Failed to set password: {}", e));
     }
+    Ok("PostgreSQL password updated.".to_string())
+}
 
-    let output = command.output().map_err(|e| format!("Failed to execute psql: {}", e))?;
+// Moves a cluster created with "initdb -A trust" to password authentication.
+// The server has to be running, since trust is the only way in without a password.
+#[tauri::command]
+pub async fn migrate_postgres_auth(instance: Option<String>) -> Result<String, String> {
+    let inst = resolve_instance("postgresql", instance.as_deref())?;
+    if !uses_trust_auth(&inst) {
+        return Ok(format!("{} already requires passwords", inst.name));
+    }
+
+    snapshot_instance(&inst, "auth-migration").await?;
+
+    let password = if inst.password.is_empty() { generate_password() } else { inst.password.clone() };
+    let client = pg_connect(&inst, "postgres").await?;
+    set_instance_password(&inst.name, &password)?;
+    let sql = format!("ALTER ROLE {} WITH PASSWORD {}", pg_ident(&inst.user), pg_literal(&pg_password_verifier(&password)));
+    client.batch_execute(&sql).await.map_err(|e| format!("Failed to set password: {}", e))?;
+
+    let hba = PathBuf::from(&inst.data_dir).join("pg_hba.conf");
+    let _ = fs::copy(&hba, hba.with_extension("conf.trust.bak"));
+    write_pg_hba(&inst)?;
+    write_postgres_config(&inst)?;
+
+    // pg_hba.conf is picked up on reload; listen_addresses only on restart
+    client.batch_execute("SELECT pg_reload_conf()").await.map_err(|e| format!("Failed to reload configuration: {}", e))?;
+
+    println!("Migrated {} from trust to {}", inst.name, pg_auth_method(&inst));
+    Ok(format!("{} now requires a password. Restart it to apply the listen address.", inst.name))
+}
 
-    if output.status.success() {
-        Ok("PostgreSQL password updated.".to_string())
+#[tauri::command]
+pub fn set_db_instance_port(name: String, port: u16) -> Result<DbInstance, String> {
+    let mut inst = load_instance(&name)?;
+    if let Some(other) = all_instances().into_iter().find(|i| i.port == port && i.name != name) {
+        return Err(format!("Port {} is already assigned to instance '{}'", port, other.name));
+    }
+    inst.port = port;
+
+    if inst.engine == "postgresql" {
+        write_postgres_config(&inst)?;
     } else {
-        let err = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to set password: {}", err))
+        write_mysql_config(&inst)?;
     }
-}
\ No newline at end of file
+    save_instance(&inst)?;
+    Ok(inst)
+}
+
+#[tauri::command]
+pub fn get_db_instance_password(name: String) -> Result<String, String> {
+    Ok(load_instance(&name)?.password)
+}
@@ -0,0 +1,408 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use crate::db_client::{create_database_if_missing, is_listening, is_mysql_family, list_databases};
+use crate::db_instances::{all_instances, find_tool, load_instance, DbInstance};
+use crate::filesystem::get_home;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupInfo {
+    // "<instance>/<database>/<timestamp>", also the file path below the backups folder
+    pub id: String,
+    pub instance: String,
+    pub engine: String,
+    pub version: String,
+    pub database: String,
+    pub file: String,
+    pub size: u64,
+    pub created_at: u64,
+    // "manual", "scheduled" or "pre-<operation>"
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    // Automatic backups kept per database; manual backups are never pruned
+    pub keep_last: usize,
+    pub max_age_days: Option<u64>,
+    pub snapshot_before_changes: bool,
+    // Empty means every instance
+    pub instances: Vec<String>,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            keep_last: 7,
+            max_age_days: None,
+            snapshot_before_changes: true,
+            instances: Vec::new(),
+        }
+    }
+}
+
+fn backups_dir() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("backups"))
+}
+
+fn services_dir() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("services"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn load_settings() -> BackupSettings {
+    backups_dir().ok()
+        .and_then(|d| fs::read_to_string(d.join("settings.json")).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn backup_path(id: &str) -> Result<PathBuf, String> {
+    if id.split('/').any(|part| part.is_empty() || part == "." || part == "..") {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    Ok(backups_dir()?.join(id))
+}
+
+fn all_backups() -> Vec<BackupInfo> {
+    let mut backups = Vec::new();
+    let root = match backups_dir() { Ok(r) => r, Err(_) => return backups };
+
+    for instance in fs::read_dir(&root).into_iter().flatten().flatten() {
+        for database in fs::read_dir(instance.path()).into_iter().flatten().flatten() {
+            for entry in fs::read_dir(database.path()).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if path.extension().map(|e| e == "json").unwrap_or(false) {
+                    if let Some(info) = fs::read_to_string(&path).ok().and_then(|d| serde_json::from_str(&d).ok()) {
+                        backups.push(info);
+                    }
+                }
+            }
+        }
+    }
+    backups.sort_by_key(|b: &BackupInfo| std::cmp::Reverse(b.created_at));
+    backups
+}
+
+fn hide_window(command: &mut Command) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = command;
+}
+
+// Client tools get the password from the environment, never from the command line
+pub fn client_command(instance: &DbInstance, mysql_tools: &[&str], pg_tool: &str) -> Result<Command, String> {
+    let service_dir = services_dir()?.join(&instance.version_folder);
+    let mut command = if is_mysql_family(&instance.engine) {
+        let tool = find_tool(&service_dir, mysql_tools)
+            .ok_or(format!("Could not find {} in {}", mysql_tools.join("/"), instance.version_folder))?;
+        let mut c = Command::new(tool);
+        c.arg("--protocol=TCP")
+            .arg("--host=127.0.0.1")
+            .arg(format!("--port={}", instance.port))
+            .arg(format!("--user={}", instance.user));
+        if !instance.password.is_empty() { c.env("MYSQL_PWD", &instance.password); }
+        c
+    } else {
+        let tool = find_tool(&service_dir, &[pg_tool])
+            .ok_or(format!("Could not find {} in {}", pg_tool, instance.version_folder))?;
+        let mut c = Command::new(tool);
+        c.args(["-h", "127.0.0.1", "-p", &instance.port.to_string(), "-U", &instance.user]);
+        if !instance.password.is_empty() { c.env("PGPASSWORD", &instance.password); }
+        c
+    };
+    hide_window(&mut command);
+    Ok(command)
+}
+
+// Collects stderr on its own thread so a chatty tool can't block on a full pipe
+pub fn drain_stderr(child: &mut std::process::Child) -> thread::JoinHandle<String> {
+    let stderr = child.stderr.take();
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut s) = stderr { let _ = s.read_to_string(&mut text); }
+        text
+    })
+}
+
+// Dumps one database as SQL on stdout
+pub fn dump_command(instance: &DbInstance, database: &str) -> Result<Command, String> {
+    let mut command = client_command(instance, &["mariadb-dump", "mysqldump"], "pg_dump")?;
+    if is_mysql_family(&instance.engine) {
+        command.args(["--single-transaction", "--routines", "--triggers", "--events", "--add-drop-table", database]);
+    } else {
+        command.args(["--clean", "--if-exists", "--no-owner", "--no-privileges", "-d", database]);
+    }
+    Ok(command)
+}
+
+// Runs SQL from stdin and stops at the first error
+pub fn import_command(instance: &DbInstance, database: &str) -> Result<Command, String> {
+    let mut command = client_command(instance, &["mariadb", "mysql"], "psql")?;
+    if is_mysql_family(&instance.engine) {
+        command.args(["--default-character-set=utf8mb4", "--max-allowed-packet=1G", database]);
+    } else {
+        command.args(["-v", "ON_ERROR_STOP=1", "-q", "-d", database]);
+    }
+    Ok(command)
+}
+
+fn dump_to_file(instance: &DbInstance, database: &str, target: &Path) -> Result<(), String> {
+    let mut command = dump_command(instance, database)?;
+    let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()
+        .map_err(|e| format!("Failed to start dump: {}", e))?;
+    let stderr = drain_stderr(&mut child);
+
+    let mut stdout = child.stdout.take().ok_or("Dump produced no output")?;
+    let written = File::create(target).and_then(|file| {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        io::copy(&mut stdout, &mut encoder)?;
+        encoder.finish().map(|_| ())
+    });
+
+    // A dump still writing into a pipe nobody reads would block forever
+    drop(stdout);
+    if written.is_err() {
+        let _ = child.kill();
+    }
+    let status = child.wait().map_err(|e| e.to_string());
+    let errors = stderr.join().unwrap_or_default();
+    let result = match (status, written) {
+        (Err(e), _) => Err(e),
+        (Ok(status), _) if !status.success() => Err(format!("Dump of {} failed: {}", database, errors.trim())),
+        (Ok(_), Err(e)) => Err(format!("Could not write {}: {}", target.display(), e)),
+        (Ok(_), Ok(())) => Ok(()),
+    };
+    // A partial file must not be picked up by retention or restore
+    if result.is_err() {
+        let _ = fs::remove_file(target);
+    }
+    result
+}
+
+pub fn restore_from_file(instance: &DbInstance, database: &str, source: &Path) -> Result<(), String> {
+    let mut command = import_command(instance, database)?;
+    let mut child = command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::piped()).spawn()
+        .map_err(|e| format!("Failed to start restore: {}", e))?;
+    let stderr = drain_stderr(&mut child);
+
+    let file = File::open(source).map_err(|e| e.to_string())?;
+    let mut decoder = GzDecoder::new(file);
+    let copied = {
+        let mut stdin = child.stdin.take().ok_or("Could not write to restore process")?;
+        // A broken pipe means the client gave up; its stderr explains why
+        io::copy(&mut decoder, &mut stdin)
+    };
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    let errors = stderr.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("Restore into {} failed: {}", database, errors.trim()));
+    }
+    // A read error with a successful client means the backup itself is truncated or corrupt
+    copied.map_err(|e| format!("Could not read {}: {}", source.display(), e))?;
+    Ok(())
+}
+
+pub async fn backup_one(instance: &DbInstance, database: &str, reason: &str) -> Result<BackupInfo, String> {
+    if database.is_empty() || database.starts_with('.') || database.contains(['/', '\\']) {
+        return Err(format!("Invalid database name: {}", database));
+    }
+    if !is_listening(instance.port) {
+        return Err(format!("Instance '{}' is not running", instance.name));
+    }
+
+    let created_at = now_secs();
+    let dir = backups_dir()?.join(&instance.name).join(database);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let file = dir.join(format!("{}.sql.gz", created_at));
+
+    let inst = instance.clone();
+    let db = database.to_string();
+    let target = file.clone();
+    tauri::async_runtime::spawn_blocking(move || dump_to_file(&inst, &db, &target))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let info = BackupInfo {
+        id: format!("{}/{}/{}", instance.name, database, created_at),
+        instance: instance.name.clone(),
+        engine: instance.engine.clone(),
+        version: instance.version.clone(),
+        database: database.to_string(),
+        file: file.to_string_lossy().to_string(),
+        size: fs::metadata(&file).map(|m| m.len()).unwrap_or(0),
+        created_at,
+        reason: reason.to_string(),
+    };
+    let meta = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    fs::write(dir.join(format!("{}.json", created_at)), meta).map_err(|e| e.to_string())?;
+
+    println!("Backed up {}/{} ({} bytes)", instance.name, database, info.size);
+    Ok(info)
+}
+
+// Drops automatic backups beyond keep_last or older than max_age_days, per database
+fn apply_retention(settings: &BackupSettings) {
+    let now = now_secs();
+    let mut seen: Vec<(String, String, usize)> = Vec::new();
+
+    for backup in all_backups() {
+        if backup.reason == "manual" { continue; }
+
+        let position = match seen.iter_mut().find(|(i, d, _)| *i == backup.instance && *d == backup.database) {
+            Some(entry) => { entry.2 += 1; entry.2 }
+            None => { seen.push((backup.instance.clone(), backup.database.clone(), 1)); 1 }
+        };
+        let too_old = settings.max_age_days.map(|days| now.saturating_sub(backup.created_at) > days * 86400).unwrap_or(false);
+
+        if position > settings.keep_last || too_old {
+            let _ = remove_backup_files(&backup.id);
+        }
+    }
+}
+
+fn remove_backup_files(id: &str) -> Result<(), String> {
+    let base = backup_path(id)?;
+    let data = base.with_extension("sql.gz");
+    let meta = base.with_extension("json");
+    if !meta.exists() { return Err(format!("Backup {} not found", id)); }
+    let _ = fs::remove_file(data);
+    fs::remove_file(meta).map_err(|e| e.to_string())
+}
+
+// Backs up every database of an instance ahead of a risky operation, if the user wants that
+pub async fn snapshot_instance(instance: &DbInstance, operation: &str) -> Result<Vec<BackupInfo>, String> {
+    let settings = load_settings();
+    if !settings.snapshot_before_changes { return Ok(Vec::new()); }
+
+    let mut backups = Vec::new();
+    for database in list_databases(instance).await? {
+        backups.push(backup_one(instance, &database, &format!("pre-{}", operation)).await?);
+    }
+    apply_retention(&settings);
+    Ok(backups)
+}
+
+async fn run_scheduled_backups() {
+    let settings = load_settings();
+    if !settings.enabled { return; }
+
+    let interval = settings.interval_hours.max(1) * 3600;
+    let backups = all_backups();
+
+    for instance in all_instances() {
+        if !settings.instances.is_empty() && !settings.instances.contains(&instance.name) { continue; }
+        if !is_listening(instance.port) { continue; }
+
+        let databases = match list_databases(&instance).await {
+            Ok(d) => d,
+            Err(e) => { println!("Scheduled backup skipped for {}: {}", instance.name, e); continue; }
+        };
+        for database in databases {
+            let last = backups.iter()
+                .filter(|b| b.instance == instance.name && b.database == database)
+                .map(|b| b.created_at)
+                .max()
+                .unwrap_or(0);
+            if now_secs().saturating_sub(last) < interval { continue; }
+
+            if let Err(e) = backup_one(&instance, &database, "scheduled").await {
+                println!("Scheduled backup of {}/{} failed: {}", instance.name, database, e);
+            }
+        }
+    }
+    apply_retention(&settings);
+}
+
+pub async fn start_backup_scheduler() {
+    loop {
+        run_scheduled_backups().await;
+        tokio::time::sleep(Duration::from_secs(10 * 60)).await;
+    }
+}
+
+#[tauri::command]
+pub async fn backup_database(instance: String, database: String) -> Result<BackupInfo, String> {
+    let inst = load_instance(&instance)?;
+    backup_one(&inst, &database, "manual").await
+}
+
+#[tauri::command]
+pub async fn restore_database(backup_id: String, target_database: Option<String>) -> Result<String, String> {
+    let meta = backup_path(&backup_id)?.with_extension("json");
+    let info: BackupInfo = fs::read_to_string(&meta).ok()
+        .and_then(|d| serde_json::from_str(&d).ok())
+        .ok_or(format!("Backup {} not found", backup_id))?;
+
+    let instance = load_instance(&info.instance)?;
+    if !is_listening(instance.port) {
+        return Err(format!("Instance '{}' is not running", instance.name));
+    }
+    if instance.engine != info.engine {
+        return Err(format!("Backup was taken from {} and cannot be restored into {}", info.engine, instance.engine));
+    }
+
+    let database = target_database.unwrap_or(info.database.clone());
+    create_database_if_missing(&instance, &database).await?;
+
+    let source = PathBuf::from(&info.file);
+    let db = database.clone();
+    tauri::async_runtime::spawn_blocking(move || restore_from_file(&instance, &db, &source))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(format!("Restored {} into {}", backup_id, database))
+}
+
+#[tauri::command]
+pub fn list_backups(instance: Option<String>, database: Option<String>) -> Vec<BackupInfo> {
+    all_backups()
+        .into_iter()
+        .filter(|b| instance.as_ref().map(|i| &b.instance == i).unwrap_or(true))
+        .filter(|b| database.as_ref().map(|d| &b.database == d).unwrap_or(true))
+        .collect()
+}
+
+#[tauri::command]
+pub fn delete_backup(backup_id: String) -> Result<String, String> {
+    remove_backup_files(&backup_id)?;
+    Ok(format!("Deleted backup {}", backup_id))
+}
+
+#[tauri::command]
+pub fn get_backup_settings() -> BackupSettings {
+    load_settings()
+}
+
+#[tauri::command]
+pub fn save_backup_settings(settings: BackupSettings) -> Result<String, String> {
+    let dir = backups_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let data = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(dir.join("settings.json"), data).map_err(|e| e.to_string())?;
+    apply_retention(&settings);
+    Ok("Backup settings saved".to_string())
+}
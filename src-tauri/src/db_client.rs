@@ -0,0 +1,138 @@
+use std::net::TcpStream;
+use std::time::Duration;
+use mysql_async::prelude::*;
+use mysql_async::{Conn, OptsBuilder};
+use tokio_postgres::{Client, NoTls};
+use crate::db_instances::{all_instances, DbInstance};
+
+pub fn is_mysql_family(engine: &str) -> bool {
+    matches!(engine, "mariadb" | "mysql")
+}
+
+// Accepts the names people actually type for each engine
+pub fn normalize_engine(engine: &str) -> Result<&'static str, String> {
+    match engine.to_lowercase().as_str() {
+        "mariadb" | "mysql" => Ok("mysql"),
+        "postgresql" | "postgres" | "pgsql" | "pg" => Ok("postgresql"),
+        other => Err(format!("Unsupported database engine: {}", other)),
+    }
+}
+
+pub fn is_listening(port: u16) -> bool {
+    let addr = format!("127.0.0.1:{}", port);
+    TcpStream::connect_timeout(&addr.parse().unwrap(), Duration::from_millis(200)).is_ok()
+}
+
+// Picks the named instance, or the first running one of the requested engine
+pub fn resolve_instance(engine: &str, instance: Option<&str>) -> Result<DbInstance, String> {
+    let family = normalize_engine(engine)?;
+    let matches_engine = |i: &DbInstance| (family == "mysql") == is_mysql_family(&i.engine);
+
+    if let Some(name) = instance {
+        let inst = running_instance(name)?;
+        if !matches_engine(&inst) {
+            return Err(format!("Instance '{}' is a {} instance", name, inst.engine));
+        }
+        return Ok(inst);
+    }
+
+    all_instances()
+        .into_iter()
+        .find(|i| matches_engine(i) && is_listening(i.port))
+        .ok_or(format!("No running {} instance found. Start one first.", engine))
+}
+
+pub fn running_instance(name: &str) -> Result<DbInstance, String> {
+    let inst = crate::db_instances::load_instance(name)?;
+    if !is_listening(inst.port) {
+        return Err(format!("Instance '{}' is not running (port {})", name, inst.port));
+    }
+    Ok(inst)
+}
+
+pub async fn mysql_connect(instance: &DbInstance, database: Option<&str>) -> Result<Conn, String> {
+    let opts = OptsBuilder::default()
+        .ip_or_hostname("127.0.0.1")
+        .tcp_port(instance.port)
+        .user(Some(instance.user.clone()))
+        .pass(if instance.password.is_empty() { None } else { Some(instance.password.clone()) })
+        .db_name(database.map(str::to_string));
+
+    Conn::new(opts).await.map_err(|e| format!("Could not connect to {} on port {}: {}", instance.name, instance.port, e))
+}
+
+pub async fn pg_connect(instance: &DbInstance, database: &str) -> Result<Client, String> {
+    let mut config = tokio_postgres::Config::new();
+    config.host("127.0.0.1").port(instance.port).user(&instance.user).dbname(database);
+    if !instance.password.is_empty() {
+        config.password(&instance.password);
+    }
+
+    let (client, connection) = config.connect(NoTls).await
+        .map_err(|e| format!("Could not connect to {} on port {}: {}", instance.name, instance.port, e))?;
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = connection.await {
+            println!("PostgreSQL connection error: {}", e);
+        }
+    });
+    Ok(client)
+}
+
+pub fn mysql_ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+// Doubled quotes work with and without NO_BACKSLASH_ESCAPES; backslashes assume the default sql_mode
+pub fn mysql_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+pub fn pg_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// Assumes standard_conforming_strings (the default since 9.1), where only quotes need doubling
+pub fn pg_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// SCRAM-SHA-256 verifier computed client-side, so the plain password never appears in SQL or server logs
+pub fn pg_password_verifier(password: &str) -> String {
+    postgres_protocol::password::scram_sha_256(password.as_bytes())
+}
+
+// User databases on an instance, without the engine's own schemas
+pub async fn list_databases(instance: &DbInstance) -> Result<Vec<String>, String> {
+    if is_mysql_family(&instance.engine) {
+        let mut conn = mysql_connect(instance, None).await?;
+        let names: Vec<String> = conn.query("SHOW DATABASES").await.map_err(|e| format!("MySQL error: {}", e))?;
+        let _ = conn.disconnect().await;
+        Ok(names.into_iter()
+            .filter(|n| !matches!(n.as_str(), "information_schema" | "performance_schema" | "mysql" | "sys"))
+            .collect())
+    } else {
+        let client = pg_connect(instance, "postgres").await?;
+        let rows = client.query("SELECT datname FROM pg_database WHERE NOT datistemplate AND datname <> 'postgres' ORDER BY datname", &[]).await
+            .map_err(|e| format!("PostgreSQL error: {}", e))?;
+        Ok(rows.iter().map(|r| r.get::<_, String>(0)).collect())
+    }
+}
+
+pub async fn create_database_if_missing(instance: &DbInstance, database: &str) -> Result<(), String> {
+    if is_mysql_family(&instance.engine) {
+        let mut conn = mysql_connect(instance, None).await?;
+        conn.query_drop(format!("CREATE DATABASE IF NOT EXISTS {} CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", mysql_ident(database))).await
+            .map_err(|e| format!("MySQL error: {}", e))?;
+        let _ = conn.disconnect().await;
+    } else {
+        let client = pg_connect(instance, "postgres").await?;
+        let exists = !client.query("SELECT 1 FROM pg_database WHERE datname = $1", &[&database]).await
+            .map_err(|e| e.to_string())?.is_empty();
+        if !exists {
+            client.batch_execute(&format!("CREATE DATABASE {} ENCODING 'UTF8'", pg_ident(database))).await
+                .map_err(|e| format!("PostgreSQL error: {}", e))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::net::TcpListener;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::credentials::{delete_secret, get_secret, instance_account, set_secret};
+use crate::database::{init_mysql, init_postgresql};
+use crate::filesystem::get_home;
+use crate::process_manager::{launch_service, stop_service_by_id, LaunchSpec, ServiceState};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DbInstance {
+    pub name: String,
+    // "mariadb", "mysql" or "postgresql"
+    pub engine: String,
+    pub version_folder: String,
+    // Major line the data directory was initialized with, e.g. "10.6" or "16"
+    pub version: String,
+    pub port: u16,
+    pub data_dir: String,
+    pub config_file: String,
+    #[serde(default)]
+    pub user: String,
+    // Kept in the OS keyring; only in instance.json for older files not migrated yet
+    #[serde(default, skip_serializing)]
+    pub password: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DbInstanceInfo {
+    #[serde(flatten)]
+    pub instance: DbInstance,
+    pub running: bool,
+}
+
+pub fn instances_root() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("data").join("instances"))
+}
+
+pub fn validate_instance_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid { Ok(()) } else { Err(format!("Invalid instance name '{}'. Use letters, digits, '-', '_' or '.'", name)) }
+}
+
+pub fn instance_dir(name: &str) -> Result<PathBuf, String> {
+    validate_instance_name(name)?;
+    Ok(instances_root()?.join(name))
+}
+
+pub fn load_instance(name: &str) -> Result<DbInstance, String> {
+    let path = instance_dir(name)?.join("instance.json");
+    let data = fs::read_to_string(&path).map_err(|_| format!("Database instance '{}' not found", name))?;
+    let mut instance: DbInstance = serde_json::from_str(&data).map_err(|e| format!("Invalid instance.json for {}: {}", name, e))?;
+    if instance.user.is_empty() {
+        instance.user = default_user(&instance.engine).to_string();
+    }
+
+    if instance.password.is_empty() {
+        instance.password = get_secret(&instance_account(name)).unwrap_or_default();
+    } else {
+        // Plain-text password from an older instance.json; move it to the keyring, or leave it until the keyring works
+        match set_secret(&instance_account(name), &instance.password) {
+            Ok(()) => save_instance(&instance)?,
+            Err(e) => println!("Keeping the password of {} in instance.json: {}", name, e),
+        }
+    }
+    Ok(instance)
+}
+
+pub fn set_instance_password(name: &str, password: &str) -> Result<(), String> {
+    if password.is_empty() {
+        delete_secret(&instance_account(name));
+        Ok(())
+    } else {
+        set_secret(&instance_account(name), password)
+    }
+}
+
+pub fn save_instance(instance: &DbInstance) -> Result<(), String> {
+    let dir = instance_dir(&instance.name)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let mut value = serde_json::to_value(instance).map_err(|e| e.to_string())?;
+    // A legacy password that could not be moved to the keyring stays in the file, or it would be lost
+    if !instance.password.is_empty() && get_secret(&instance_account(&instance.name)).as_deref() != Some(instance.password.as_str()) {
+        value["password"] = instance.password.clone().into();
+    }
+    let data = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    fs::write(dir.join("instance.json"), data).map_err(|e| e.to_string())
+}
+
+pub fn all_instances() -> Vec<DbInstance> {
+    let mut instances: Vec<DbInstance> = instances_root()
+        .ok()
+        .and_then(|root| fs::read_dir(root).ok())
+        .map(|entries| entries.flatten()
+            .filter_map(|e| load_instance(&e.file_name().to_string_lossy()).ok())
+            .collect())
+        .unwrap_or_default();
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    instances
+}
+
+pub fn default_user(engine: &str) -> &'static str {
+    if engine == "postgresql" { "postgres" } else { "root" }
+}
+
+pub fn default_port(engine: &str) -> u16 {
+    if engine == "postgresql" { 5432 } else { 3306 }
+}
+
+// First port from the engine default upwards that no instance claims and nothing listens on
+pub fn next_free_port(engine: &str) -> Result<u16, String> {
+    let taken: Vec<u16> = all_instances().iter().map(|i| i.port).collect();
+    let mut port = default_port(engine);
+    while taken.contains(&port) || TcpListener::bind(("127.0.0.1", port)).is_err() {
+        port = port.checked_add(1).ok_or(format!("No free port left above {}", default_port(engine)))?;
+    }
+    Ok(port)
+}
+
+pub fn service_id(name: &str) -> String {
+    format!("db_{}", name)
+}
+
+// ("mariadb", "10.6") from "mariadb-10.6.16-winx64"; ("postgresql", "16") from "postgresql-16.2"
+pub fn engine_and_version(version_folder: &str) -> Option<(String, String)> {
+    let lower = version_folder.to_lowercase();
+    let engine = if lower.contains("mariadb") { "mariadb" }
+        else if lower.contains("mysql") { "mysql" }
+        else if lower.contains("postgres") || lower.contains("pgsql") { "postgresql" }
+        else { return None };
+
+    let version = lower.split(['-', '_'])
+        .find(|part| part.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false))?;
+    let parts: Vec<&str> = version.split('.').collect();
+
+    // PostgreSQL 10+ uses the first number as its major version
+    let major = if engine == "postgresql" && parts[0].parse::<u32>().map(|m| m >= 10).unwrap_or(false) {
+        parts[0].to_string()
+    } else {
+        parts.iter().take(2).cloned().collect::<Vec<_>>().join(".")
+    };
+
+    Some((engine.to_string(), major))
+}
+
+pub fn exe(name: &str) -> String {
+    if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() }
+}
+
+// Looks in bin/ and scripts/ (Linux tarballs keep mariadb-install-db there), directly or one level down
+pub fn find_tool(service_dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    let mut roots = vec![service_dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(service_dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                roots.push(entry.path());
+            }
+        }
+    }
+
+    for name in names {
+        for root in &roots {
+            for sub in ["bin", "scripts"] {
+                let candidate = root.join(sub).join(exe(name));
+                if candidate.is_file() { return Some(candidate); }
+            }
+        }
+    }
+    None
+}
+
+// Config files use forward slashes on every platform; MySQL and PostgreSQL both accept them on Windows
+pub fn config_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn services_dir() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("services"))
+}
+
+fn is_running(state: &ServiceState, name: &str) -> bool {
+    state.pids.lock().map(|p| p.contains_key(&service_id(name))).unwrap_or(false)
+}
+
+#[tauri::command]
+pub fn list_db_instances(state: State<ServiceState>) -> Result<Vec<DbInstanceInfo>, String> {
+    Ok(all_instances()
+        .into_iter()
+        .map(|instance| {
+            let running = is_running(&state, &instance.name);
+            DbInstanceInfo { instance, running }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn create_db_instance(name: String, version_folder: String, port: Option<u16>) -> Result<DbInstance, String> {
+    validate_instance_name(&name)?;
+    if load_instance(&name).is_ok() {
+        return Err(format!("Database instance '{}' already exists", name));
+    }
+
+    let (engine, _) = engine_and_version(&version_folder)
+        .ok_or(format!("{} is not a MariaDB, MySQL or PostgreSQL build", version_folder))?;
+    if !services_dir()?.join(&version_folder).exists() {
+        return Err(format!("Service {} not installed", version_folder));
+    }
+
+    let port = match port {
+        Some(p) => p,
+        None => next_free_port(&engine)?,
+    };
+    if let Some(other) = all_instances().into_iter().find(|i| i.port == port) {
+        return Err(format!("Port {} is already assigned to instance '{}'", port, other.name));
+    }
+
+    let result = if engine == "postgresql" {
+        init_postgresql(version_folder, Some(name.clone()), Some(port))
+    } else {
+        init_mysql(version_folder, Some(name.clone()), Some(port))
+    };
+
+    // Don't leave a half-initialized instance behind
+    if result.is_err() {
+        if let Ok(dir) = instance_dir(&name) {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+    result
+}
+
+pub fn launch_spec(instance: &DbInstance) -> Result<LaunchSpec, String> {
+    let service_dir = services_dir()?.join(&instance.version_folder);
+    let (bin, args) = if instance.engine == "postgresql" {
+        let postgres = find_tool(&service_dir, &["postgres"])
+            .ok_or(format!("Could not find postgres in {}", instance.version_folder))?;
+        (postgres, vec!["-D".to_string(), instance.data_dir.clone(), "-p".to_string(), instance.port.to_string()])
+    } else {
+        let mysqld = find_tool(&service_dir, &["mariadbd", "mysqld"])
+            .ok_or(format!("Could not find mysqld in {}", instance.version_folder))?;
+        // --defaults-file must be the first argument
+        (mysqld, vec![format!("--defaults-file={}", instance.config_file), "--console".to_string()])
+    };
+
+    Ok(LaunchSpec {
+        bin_path: bin.to_string_lossy().to_string(),
+        args,
+        cwd: None,
+        env_paths: None,
+        port: Some(instance.port),
+    })
+}
+
+#[tauri::command]
+pub async fn start_db_instance(state: State<'_, ServiceState>, name: String) -> Result<String, String> {
+    let instance = load_instance(&name)?;
+    // Adopted data with no recorded server version could be damaged by the wrong server
+    if instance.version == "unknown" {
+        return Err(format!("Instance '{}' holds data from an unknown server version and won't be started automatically", name));
+    }
+    launch_service(&state, service_id(&name), launch_spec(&instance)?)
+}
+
+#[tauri::command]
+pub fn stop_db_instance(state: State<ServiceState>, name: String) -> Result<String, String> {
+    stop_service_by_id(&state, &service_id(&name))
+}
+
+#[tauri::command]
+pub fn delete_db_instance(state: State<ServiceState>, name: String, delete_data: Option<bool>) -> Result<String, String> {
+    let instance = load_instance(&name)?;
+    if is_running(&state, &name) {
+        stop_service_by_id(&state, &service_id(&name))?;
+    }
+
+    let dir = instance_dir(&name)?;
+    let data_dir = PathBuf::from(&instance.data_dir);
+
+    // Adopted legacy data lives outside the instance folder and is only removed on request
+    let external = !data_dir.starts_with(&dir);
+    if external && delete_data.unwrap_or(false) && data_dir.exists() {
+        fs::remove_dir_all(&data_dir).map_err(|e| format!("Failed to remove {:?}: {}", data_dir, e))?;
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to remove {:?}: {}", dir, e))?;
+    delete_secret(&instance_account(&name));
+
+    if external && !delete_data.unwrap_or(false) {
+        Ok(format!("Deleted instance {} (data kept in {})", name, instance.data_dir))
+    } else {
+        Ok(format!("Deleted instance {}", name))
+    }
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use mysql_async::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::db_client::{is_listening, is_mysql_family, mysql_connect, mysql_ident, mysql_literal, normalize_engine, pg_connect, pg_ident, pg_literal, pg_password_verifier, resolve_instance};
+use crate::db_backup::backup_one;
+use crate::credentials::{delete_secret, generate_password, get_secret, project_db_account, set_secret};
+use crate::db_instances::{load_instance, DbInstance};
+use crate::env_file::update_env_file;
+use crate::filesystem::get_home;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectDatabase {
+    pub project_path: String,
+    pub instance: String,
+    // "mysql" or "postgresql"
+    pub engine: String,
+    pub database: String,
+    pub username: String,
+    pub host: String,
+    pub port: u16,
+    // Only returned to the caller; it is kept in the keyring and the project's .env
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+}
+
+fn registry_path() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("data").join("project_databases.json"))
+}
+
+fn load_registry() -> Vec<ProjectDatabase> {
+    registry_path().ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_registry(entries: &[ProjectDatabase]) -> Result<(), String> {
+    let path = registry_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+fn same_project(a: &str, b: &str) -> bool {
+    let norm = |p: &str| p.replace('\\', "/").trim_end_matches('/').to_lowercase();
+    norm(a) == norm(b)
+}
+
+// MySQL user names, which reuse the database name, are limited to 32 characters
+const MAX_NAME_LEN: usize = 32;
+
+// "My Shop (v2)" -> "my_shop_v2", at most MAX_NAME_LEN characters
+fn database_name(project_path: &Path) -> String {
+    let folder = project_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut name: String = folder.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    while name.contains("__") { name = name.replace("__", "_"); }
+    let mut name = name.trim_matches('_').to_string();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "app_");
+    }
+    with_suffix(&name, "")
+}
+
+// Shortens the base so base plus suffix still fits in MAX_NAME_LEN
+fn with_suffix(base: &str, suffix: &str) -> String {
+    let kept: String = base.chars().take(MAX_NAME_LEN - suffix.len()).collect();
+    format!("{}{}", kept.trim_end_matches('_'), suffix)
+}
+
+// Databases and accounts that already exist on the server belong to someone else and are never adopted
+async fn name_taken(instance: &DbInstance, name: &str) -> Result<bool, String> {
+    if is_mysql_family(&instance.engine) {
+        let mut conn = mysql_connect(instance, None).await?;
+        let taken: Option<i64> = conn.exec_first(
+            "SELECT EXISTS(SELECT 1 FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?) OR EXISTS(SELECT 1 FROM mysql.user WHERE User = ?)",
+            (name, name),
+        ).await.map_err(|e| format!("MySQL error: {}", e))?;
+        let _ = conn.disconnect().await;
+        Ok(taken.unwrap_or(0) != 0)
+    } else {
+        let client = pg_connect(instance, "postgres").await?;
+        let row = client.query_one(
+            "SELECT EXISTS(SELECT 1 FROM pg_database WHERE datname = $1) OR EXISTS(SELECT 1 FROM pg_roles WHERE rolname = $1)",
+            &[&name],
+        ).await.map_err(|e| format!("PostgreSQL error: {}", e))?;
+        Ok(row.get::<_, bool>(0))
+    }
+}
+
+// Plain CREATE statements, so a name claimed since name_taken fails instead of being taken over
+async fn create_mysql(instance: &DbInstance, database: &str, username: &str, password: &str) -> Result<(), String> {
+    let mut conn = mysql_connect(instance, None).await?;
+    let db = mysql_ident(database);
+    conn.query_drop(format!("CREATE DATABASE {} CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", db)).await
+        .map_err(|e| format!("MySQL error: {}", e))?;
+    let mut created = Vec::new();
+    let result = async {
+        // TCP connections from 127.0.0.1 can match either host, depending on name resolution
+        for host in ["localhost", "127.0.0.1"] {
+            let account = format!("{}@{}", mysql_literal(username), mysql_literal(host));
+            conn.query_drop(format!("CREATE USER {} IDENTIFIED BY {}", account, mysql_literal(password))).await?;
+            created.push(account.clone());
+            conn.query_drop(format!("GRANT ALL PRIVILEGES ON {}.* TO {}", db, account)).await?;
+        }
+        conn.query_drop("FLUSH PRIVILEGES").await
+    }.await;
+
+    if result.is_err() {
+        // Only what was created just now is removed; an account that already existed is left alone
+        for account in &created {
+            let _ = conn.query_drop(format!("DROP USER {}", account)).await;
+        }
+        let _ = conn.query_drop(format!("DROP DATABASE {}", db)).await;
+    }
+    let _ = conn.disconnect().await;
+    result.map_err(|e| format!("MySQL error: {}", e))
+}
+
+async fn drop_mysql(instance: &DbInstance, database: &str, username: &str) -> Result<(), String> {
+    let mut conn = mysql_connect(instance, None).await?;
+    let statements = vec![
+        format!("DROP DATABASE IF EXISTS {}", mysql_ident(database)),
+        format!("DROP USER IF EXISTS {}@'localhost'", mysql_literal(username)),
+        format!("DROP USER IF EXISTS {}@'127.0.0.1'", mysql_literal(username)),
+    ];
+    for sql in statements {
+        conn.query_drop(&sql).await.map_err(|e| format!("MySQL error: {}", e))?;
+    }
+    let _ = conn.disconnect().await;
+    Ok(())
+}
+
+async fn create_postgres(instance: &DbInstance, database: &str, username: &str, password: &str) -> Result<(), String> {
+    let client = pg_connect(instance, "postgres").await?;
+    client.batch_execute(&format!("CREATE ROLE {} LOGIN PASSWORD {}", pg_ident(username), pg_literal(&pg_password_verifier(password)))).await
+        .map_err(|e| format!("PostgreSQL error: {}", e))?;
+
+    // CREATE DATABASE cannot run inside the implicit transaction of a multi-statement batch
+    let created = client.batch_execute(&format!("CREATE DATABASE {} OWNER {} ENCODING 'UTF8'", pg_ident(database), pg_ident(username))).await;
+    if let Err(e) = created {
+        let _ = client.batch_execute(&format!("DROP ROLE IF EXISTS {}", pg_ident(username))).await;
+        return Err(format!("PostgreSQL error: {}", e));
+    }
+    client.batch_execute(&format!("REVOKE ALL ON DATABASE {} FROM PUBLIC", pg_ident(database))).await
+        .map_err(|e| format!("PostgreSQL error: {}", e))?;
+    Ok(())
+}
+
+async fn drop_postgres(instance: &DbInstance, database: &str, username: &str) -> Result<(), String> {
+    let client = pg_connect(instance, "postgres").await?;
+    // Open connections (a running dev server) would otherwise block the drop
+    client.execute("SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = $1 AND pid <> pg_backend_pid()", &[&database]).await
+        .map_err(|e| e.to_string())?;
+    client.batch_execute(&format!("DROP DATABASE IF EXISTS {}", pg_ident(database))).await
+        .map_err(|e| format!("PostgreSQL error: {}", e))?;
+    client.batch_execute(&format!("DROP ROLE IF EXISTS {}", pg_ident(username))).await
+        .map_err(|e| format!("PostgreSQL error: {}", e))?;
+    Ok(())
+}
+
+// Creates the database and user and records them; the returned entry carries the password
+pub async fn provision_project_database(project_path: &str, engine: &str, instance: Option<&str>) -> Result<ProjectDatabase, String> {
+    let project_path = project_path.to_string();
+    let project_dir = PathBuf::from(&project_path);
+    if !project_dir.is_dir() { return Err("Project folder does not exist".to_string()); }
+
+    let family = normalize_engine(engine)?;
+    let mut registry = load_registry();
+    if registry.iter().any(|e| same_project(&e.project_path, &project_path) && e.engine == family) {
+        return Err(format!("This project already has a {} database", family));
+    }
+
+    let inst = resolve_instance(family, instance)?;
+    let base_name = database_name(&project_dir);
+    let mut database = base_name.clone();
+    let mut suffix = 2;
+    while registry.iter().any(|e| e.instance == inst.name && e.database == database) || name_taken(&inst, &database).await? {
+        database = with_suffix(&base_name, &format!("_{}", suffix));
+        suffix += 1;
+    }
+    let username = database.clone();
+    let password = generate_password();
+
+    // Stored first, so a keyring failure leaves no user behind with a password nobody knows
+    let account = project_db_account(&inst.name, &database);
+    set_secret(&account, &password)?;
+    let created = if family == "mysql" {
+        create_mysql(&inst, &database, &username, &password).await
+    } else {
+        create_postgres(&inst, &database, &username, &password).await
+    };
+    if let Err(e) = created {
+        delete_secret(&account);
+        return Err(e);
+    }
+
+    let entry = ProjectDatabase {
+        project_path: project_path.clone(),
+        instance: inst.name.clone(),
+        engine: family.to_string(),
+        database: database.clone(),
+        username: username.clone(),
+        host: "127.0.0.1".to_string(),
+        port: inst.port,
+        password: String::new(),
+    };
+    registry.push(entry.clone());
+    save_registry(&registry)?;
+
+    println!("Provisioned {} database '{}' on {}", family, entry.database, inst.name);
+    Ok(ProjectDatabase { password, ..entry })
+}
+
+#[tauri::command]
+pub async fn create_project_database(project_path: String, engine: String, instance: Option<String>) -> Result<ProjectDatabase, String> {
+    let entry = provision_project_database(&project_path, &engine, instance.as_deref()).await?;
+
+    let connection = if entry.engine == "mysql" { "mysql" } else { "pgsql" };
+    update_env_file(Path::new(&project_path), &[
+        ("DB_CONNECTION", connection.to_string()),
+        ("DB_HOST", entry.host.clone()),
+        ("DB_PORT", entry.port.to_string()),
+        ("DB_DATABASE", entry.database.clone()),
+        ("DB_USERNAME", entry.username.clone()),
+        ("DB_PASSWORD", entry.password.clone()),
+    ])?;
+    Ok(entry)
+}
+
+// The database already provisioned for a project, with its password filled in from the keyring
+pub fn existing_project_database(project_path: &str, engine: Option<&str>) -> Option<ProjectDatabase> {
+    load_registry()
+        .into_iter()
+        .find(|e| same_project(&e.project_path, project_path) && engine.map(|en| en == e.engine).unwrap_or(true))
+        .map(|e| ProjectDatabase {
+            password: get_secret(&project_db_account(&e.instance, &e.database)).unwrap_or_default(),
+            ..e
+        })
+}
+
+#[tauri::command]
+pub fn list_project_databases(project_path: Option<String>) -> Vec<ProjectDatabase> {
+    load_registry()
+        .into_iter()
+        .filter(|e| project_path.as_ref().map(|p| same_project(&e.project_path, p)).unwrap_or(true))
+        .collect()
+}
+
+// Drops every database provisioned for a project. Entries whose instance is gone are forgotten;
+// entries whose instance is stopped are kept so the drop can be retried later.
+pub async fn drop_databases_for(project_path: &str) -> Result<Vec<String>, String> {
+    let registry = load_registry();
+    let (targets, mut remaining): (Vec<ProjectDatabase>, Vec<ProjectDatabase>) = registry
+        .into_iter()
+        .partition(|e| same_project(&e.project_path, project_path));
+
+    let mut dropped = Vec::new();
+    let mut errors = Vec::new();
+
+    for entry in targets {
+        let inst = match load_instance(&entry.instance) {
+            Ok(i) => i,
+            Err(_) => continue,
+        };
+        if !is_listening(inst.port) {
+            errors.push(format!("{} is not running; {} was not dropped", inst.name, entry.database));
+            remaining.push(entry);
+            continue;
+        }
+
+        // Dropping can't be undone, so keep a backup the user can restore from
+        if let Err(e) = backup_one(&inst, &entry.database, "pre-drop").await {
+            errors.push(format!("{}: backup failed, not dropped: {}", entry.database, e));
+            remaining.push(entry);
+            continue;
+        }
+
+        let result = if entry.engine == "mysql" {
+            drop_mysql(&inst, &entry.database, &entry.username).await
+        } else {
+            drop_postgres(&inst, &entry.database, &entry.username).await
+        };
+        match result {
+            Ok(()) => {
+                delete_secret(&project_db_account(&entry.instance, &entry.database));
+                dropped.push(entry.database.clone());
+            }
+            Err(e) => {
+                errors.push(format!("{}: {}", entry.database, e));
+                remaining.push(entry);
+            }
+        }
+    }
+
+    save_registry(&remaining)?;
+    if errors.is_empty() { Ok(dropped) } else { Err(errors.join("; ")) }
+}
+
+#[tauri::command]
+pub async fn drop_project_database(project_path: String) -> Result<String, String> {
+    let dropped = drop_databases_for(&project_path).await?;
+    if dropped.is_empty() {
+        Ok("No databases were provisioned for this project".to_string())
+    } else {
+        Ok(format!("Dropped {}", dropped.join(", ")))
+    }
+}
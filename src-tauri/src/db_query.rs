@@ -0,0 +1,597 @@
+use std::time::Instant;
+use mysql_async::prelude::*;
+use mysql_async::{Row, Value};
+use serde::Serialize;
+use tokio_postgres::SimpleQueryMessage;
+use crate::db_client::{is_mysql_family, list_databases, mysql_connect, pg_connect, pg_ident, running_instance};
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+// Statements that only read; anything else needs the caller's confirmation
+const READ_ONLY_KEYWORDS: [&str; 8] = ["SELECT", "SHOW", "DESCRIBE", "DESC", "EXPLAIN", "WITH", "VALUES", "TABLE"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TableInfo {
+    // Only set for PostgreSQL; MySQL databases have no schemas inside them
+    pub schema: Option<String>,
+    pub name: String,
+    pub kind: String,
+    pub rows_estimate: Option<i64>,
+    pub size_bytes: Option<i64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub extra: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IndexInfo {
+    pub name: String,
+    pub unique: bool,
+    pub primary: bool,
+    pub columns: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TableStructure {
+    pub columns: Vec<ColumnInfo>,
+    pub indexes: Vec<IndexInfo>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct QueryPage {
+    pub columns: Vec<String>,
+    // Values are returned as the server's text representation; NULL is null
+    pub rows: Vec<Vec<Option<String>>>,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+    pub affected_rows: u64,
+    pub mutation: bool,
+    pub elapsed_ms: u128,
+}
+
+// Splits on semicolons outside quotes, comments and PostgreSQL dollar-quoted bodies
+fn split_statements(sql: &str, mysql: bool) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '\'' || c == '"' || (mysql && c == '`') {
+            current.push(c);
+            i += 1;
+            while i < chars.len() {
+                current.push(chars[i]);
+                if mysql && chars[i] == '\\' && i + 1 < chars.len() {
+                    current.push(chars[i + 1]);
+                    i += 2;
+                    continue;
+                }
+                i += 1;
+                if chars[i - 1] == c {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if (c == '-' && next == Some('-')) || (mysql && c == '#') {
+            while i < chars.len() && chars[i] != '\n' {
+                current.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+
+        if c == '/' && next == Some('*') {
+            current.push_str("/*");
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                current.push(chars[i]);
+                i += 1;
+            }
+            if i < chars.len() {
+                current.push_str("*/");
+                i += 2;
+            }
+            continue;
+        }
+
+        if !mysql && c == '$' {
+            let tag_end = chars[i + 1..].iter().position(|ch| !(ch.is_alphanumeric() || *ch == '_')).map(|p| i + 1 + p);
+            if let Some(end) = tag_end.filter(|e| chars[*e] == '$') {
+                let tag: String = chars[i..=end].iter().collect();
+                current.push_str(&tag);
+                i = end + 1;
+                let rest: String = chars[i..].iter().collect();
+                match rest.find(&tag) {
+                    Some(pos) => {
+                        let body = &rest[..pos + tag.len()];
+                        current.push_str(body);
+                        i += body.chars().count();
+                    }
+                    None => {
+                        current.push_str(&rest);
+                        i = chars.len();
+                    }
+                }
+                continue;
+            }
+        }
+
+        if c == ';' {
+            if !current.trim().is_empty() {
+                statements.push(current.trim().to_string());
+            }
+            current.clear();
+        } else {
+            current.push(c);
+        }
+        i += 1;
+    }
+
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+// First keyword of a statement, skipping leading comments and parentheses
+fn leading_keyword(statement: &str) -> String {
+    let mut rest = statement.trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix("--").or_else(|| rest.strip_prefix('#')) {
+            rest = after.split_once('\n').map(|(_, r)| r).unwrap_or("").trim_start();
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.split_once("*/").map(|(_, r)| r).unwrap_or("").trim_start();
+        } else if let Some(after) = rest.strip_prefix('(') {
+            rest = after.trim_start();
+        } else {
+            break;
+        }
+    }
+    rest.chars().take_while(|c| c.is_ascii_alphabetic()).collect::<String>().to_uppercase()
+}
+
+fn is_read_only(statement: &str) -> bool {
+    let keyword = leading_keyword(statement);
+    if !READ_ONLY_KEYWORDS.contains(&keyword.as_str()) {
+        return false;
+    }
+    let upper = statement.to_uppercase();
+    let words: Vec<&str> = upper
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    // SELECT ... INTO OUTFILE writes files on the server; FOR UPDATE/SHARE takes row locks
+    let writes_or_locks = words.windows(2).any(|pair| matches!(
+        (pair[0], pair[1]),
+        ("INTO", "OUTFILE") | ("INTO", "DUMPFILE") | ("FOR", "UPDATE") | ("FOR", "SHARE")
+            | ("KEY", "UPDATE") | ("KEY", "SHARE") | ("IN", "SHARE")
+    ));
+    if writes_or_locks {
+        return false;
+    }
+    // PostgreSQL allows INSERT/UPDATE/DELETE inside a WITH clause
+    if keyword == "WITH" {
+        return !words.iter().any(|word| matches!(*word, "INSERT" | "UPDATE" | "DELETE" | "MERGE"));
+    }
+    true
+}
+
+fn mysql_value(value: &Value) -> Option<String> {
+    match value {
+        Value::NULL => None,
+        Value::Bytes(bytes) => Some(match String::from_utf8(bytes.clone()) {
+            Ok(text) => text,
+            Err(_) => format!("0x{}", bytes.iter().map(|b| format!("{:02X}", b)).collect::<String>()),
+        }),
+        Value::Int(v) => Some(v.to_string()),
+        Value::UInt(v) => Some(v.to_string()),
+        Value::Float(v) => Some(v.to_string()),
+        Value::Double(v) => Some(v.to_string()),
+        Value::Date(y, m, d, h, i, s, us) => Some(format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}", y, m, d, h, i, s, us)),
+        Value::Time(neg, days, h, i, s, us) => {
+            let hours = *days as u64 * 24 + *h as u64;
+            Some(format!("{}{:02}:{:02}:{:02}.{:06}", if *neg { "-" } else { "" }, hours, i, s, us))
+        }
+    }
+}
+
+fn mysql_err(e: mysql_async::Error) -> String {
+    format!("MySQL error: {}", e)
+}
+
+fn pg_err(e: tokio_postgres::Error) -> String {
+    match e.as_db_error() {
+        Some(db) => format!("PostgreSQL error: {}", db.message()),
+        None => format!("PostgreSQL error: {}", e),
+    }
+}
+
+async fn run_mysql(
+    instance: &crate::db_instances::DbInstance,
+    database: Option<&str>,
+    sql: &str,
+    read_only: bool,
+    page: &mut QueryPage,
+) -> Result<(), String> {
+    let mut conn = mysql_connect(instance, database).await?;
+    let offset = page.page as u64 * page.page_size as u64;
+
+    if read_only {
+        conn.query_drop("START TRANSACTION READ ONLY").await.map_err(mysql_err)?;
+    }
+    // Caps what the server sends for SELECTs without rewriting the user's query
+    conn.query_drop(format!("SET SESSION sql_select_limit = {}", offset + page.page_size as u64 + 1)).await.map_err(mysql_err)?;
+
+    let mut result = conn.query_iter(sql).await.map_err(mysql_err)?;
+    loop {
+        let columns: Vec<String> = result.columns_ref().iter().map(|c| c.name_str().to_string()).collect();
+        if columns.is_empty() {
+            page.affected_rows += result.affected_rows();
+        }
+
+        let mut index = 0u64;
+        let mut rows = Vec::new();
+        let page_size = page.page_size as usize;
+        result.for_each(|row: Row| {
+            if index >= offset && rows.len() <= page_size {
+                rows.push((0..row.len()).map(|i| row.as_ref(i).and_then(mysql_value)).collect());
+            }
+            index += 1;
+        }).await.map_err(mysql_err)?;
+
+        // With several statements the UI shows the last result set
+        if !columns.is_empty() {
+            page.has_more = rows.len() > page_size;
+            rows.truncate(page_size);
+            page.columns = columns;
+            page.rows = rows;
+        }
+        if result.is_empty() {
+            break;
+        }
+    }
+    drop(result);
+
+    if read_only {
+        let _ = conn.query_drop("ROLLBACK").await;
+    }
+    let _ = conn.disconnect().await;
+    Ok(())
+}
+
+fn collect_pg_messages(messages: Vec<SimpleQueryMessage>, skip: usize, page: &mut QueryPage) {
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+    let mut seen = 0usize;
+
+    for message in messages {
+        match message {
+            SimpleQueryMessage::RowDescription(cols) => {
+                columns = cols.iter().map(|c| c.name().to_string()).collect();
+                rows.clear();
+                seen = 0;
+            }
+            SimpleQueryMessage::Row(row) => {
+                if seen >= skip && rows.len() <= page.page_size as usize {
+                    rows.push((0..row.len()).map(|i| row.get(i).map(str::to_string)).collect());
+                }
+                seen += 1;
+            }
+            SimpleQueryMessage::CommandComplete(count) => {
+                if columns.is_empty() {
+                    page.affected_rows += count;
+                } else {
+                    page.has_more = rows.len() > page.page_size as usize;
+                    rows.truncate(page.page_size as usize);
+                    page.columns = std::mem::take(&mut columns);
+                    page.rows = std::mem::take(&mut rows);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn run_postgres(
+    instance: &crate::db_instances::DbInstance,
+    database: &str,
+    statements: &[String],
+    read_only: bool,
+    page: &mut QueryPage,
+) -> Result<(), String> {
+    let client = pg_connect(instance, database).await?;
+    let offset = page.page as u64 * page.page_size as u64;
+    let keyword = leading_keyword(&statements[0]);
+
+    // A single plain query is paged through a cursor so large tables never leave the server
+    if read_only && statements.len() == 1 && matches!(keyword.as_str(), "SELECT" | "WITH" | "VALUES" | "TABLE") {
+        client.batch_execute("BEGIN READ ONLY").await.map_err(pg_err)?;
+        let result = async {
+            client.batch_execute(&format!("DECLARE stackmanager_page NO SCROLL CURSOR FOR {}", statements[0])).await?;
+            if offset > 0 {
+                client.batch_execute(&format!("MOVE FORWARD {} IN stackmanager_page", offset)).await?;
+            }
+            client.simple_query(&format!("FETCH FORWARD {} FROM stackmanager_page", page.page_size as u64 + 1)).await
+        }.await;
+        let _ = client.batch_execute("ROLLBACK").await;
+        collect_pg_messages(result.map_err(pg_err)?, 0, page);
+        page.affected_rows = 0;
+        return Ok(());
+    }
+
+    let sql = statements.join(";\n");
+    if read_only {
+        client.batch_execute("BEGIN READ ONLY").await.map_err(pg_err)?;
+    }
+    let result = client.simple_query(&sql).await;
+    if read_only {
+        let _ = client.batch_execute("ROLLBACK").await;
+    }
+    collect_pg_messages(result.map_err(pg_err)?, offset as usize, page);
+    Ok(())
+}
+
+// Runs SQL against a managed instance and returns one page of the last result set.
+// Anything that is not a plain read is refused unless `confirm` is set; unconfirmed
+// queries also run in a read-only transaction so misclassified writes fail safely.
+#[tauri::command]
+pub async fn run_sql_query(
+    instance: String,
+    database: Option<String>,
+    sql: String,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    confirm: Option<bool>,
+) -> Result<QueryPage, String> {
+    let inst = running_instance(&instance)?;
+    let mysql = is_mysql_family(&inst.engine);
+    let statements = split_statements(&sql, mysql);
+    if statements.is_empty() {
+        return Err("Query is empty".to_string());
+    }
+
+    let mutation = !statements.iter().all(|s| is_read_only(s));
+    let confirm = confirm.unwrap_or(false);
+    if mutation && !confirm {
+        return Err("This query modifies data or schema. Confirm to run it.".to_string());
+    }
+
+    let mut result = QueryPage {
+        page: page.unwrap_or(0),
+        page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        mutation,
+        ..Default::default()
+    };
+    let started = Instant::now();
+
+    if mysql {
+        run_mysql(&inst, database.as_deref(), &sql, !confirm, &mut result).await?;
+    } else {
+        run_postgres(&inst, database.as_deref().unwrap_or("postgres"), &statements, !confirm, &mut result).await?;
+    }
+
+    result.elapsed_ms = started.elapsed().as_millis();
+    println!("Query on {} returned {} rows ({} affected)", instance, result.rows.len(), result.affected_rows);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_instance_databases(instance: String) -> Result<Vec<String>, String> {
+    let inst = running_instance(&instance)?;
+    list_databases(&inst).await
+}
+
+#[tauri::command]
+pub async fn list_tables(instance: String, database: String) -> Result<Vec<TableInfo>, String> {
+    let inst = running_instance(&instance)?;
+
+    if is_mysql_family(&inst.engine) {
+        let mut conn = mysql_connect(&inst, Some(&database)).await?;
+        let tables = conn.exec_map(
+            "SELECT TABLE_NAME, TABLE_TYPE, TABLE_ROWS, DATA_LENGTH + INDEX_LENGTH FROM information_schema.TABLES WHERE TABLE_SCHEMA = ? ORDER BY TABLE_NAME",
+            (database.clone(),),
+            |(name, kind, rows, size): (String, String, Option<u64>, Option<u64>)| TableInfo {
+                schema: None,
+                name,
+                kind: if kind == "VIEW" { "view".to_string() } else { "table".to_string() },
+                rows_estimate: rows.map(|r| r as i64),
+                size_bytes: size.map(|s| s as i64),
+            },
+        ).await.map_err(mysql_err)?;
+        let _ = conn.disconnect().await;
+        Ok(tables)
+    } else {
+        let client = pg_connect(&inst, &database).await?;
+        let rows = client.query(
+            "SELECT n.nspname::text, c.relname::text, c.relkind::text, c.reltuples::bigint, pg_total_relation_size(c.oid) \
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f') \
+               AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%' \
+             ORDER BY 1, 2",
+            &[],
+        ).await.map_err(pg_err)?;
+
+        Ok(rows.iter().map(|r| {
+            let kind: String = r.get(2);
+            let estimate: i64 = r.get(3);
+            TableInfo {
+                schema: Some(r.get(0)),
+                name: r.get(1),
+                kind: match kind.as_str() {
+                    "v" => "view",
+                    "m" => "materialized_view",
+                    "f" => "foreign_table",
+                    _ => "table",
+                }.to_string(),
+                // reltuples is -1 until the table has been analyzed
+                rows_estimate: if estimate < 0 { None } else { Some(estimate) },
+                size_bytes: Some(r.get(4)),
+            }
+        }).collect())
+    }
+}
+
+#[tauri::command]
+pub async fn describe_table(
+    instance: String,
+    database: String,
+    table: String,
+    schema: Option<String>,
+) -> Result<TableStructure, String> {
+    let inst = running_instance(&instance)?;
+
+    if is_mysql_family(&inst.engine) {
+        let mut conn = mysql_connect(&inst, Some(&database)).await?;
+        let columns = conn.exec_map(
+            "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, EXTRA FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (database.clone(), table.clone()),
+            |(name, data_type, nullable, default, extra): (String, String, String, Option<String>, Option<String>)| ColumnInfo {
+                name,
+                data_type,
+                nullable: nullable == "YES",
+                default,
+                extra: extra.filter(|e| !e.is_empty()),
+            },
+        ).await.map_err(mysql_err)?;
+
+        let index_rows: Vec<(String, i64, String)> = conn.exec(
+            "SELECT INDEX_NAME, NON_UNIQUE, COLUMN_NAME FROM information_schema.STATISTICS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY INDEX_NAME, SEQ_IN_INDEX",
+            (database.clone(), table.clone()),
+        ).await.map_err(mysql_err)?;
+        let _ = conn.disconnect().await;
+
+        if columns.is_empty() {
+            return Err(format!("Table '{}' not found in {}", table, database));
+        }
+
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for (name, non_unique, column) in index_rows {
+            match indexes.last_mut() {
+                Some(last) if last.name == name => last.columns.push(column),
+                _ => indexes.push(IndexInfo {
+                    primary: name == "PRIMARY",
+                    unique: non_unique == 0,
+                    name,
+                    columns: vec![column],
+                }),
+            }
+        }
+        Ok(TableStructure { columns, indexes })
+    } else {
+        let client = pg_connect(&inst, &database).await?;
+        let qualified = format!("{}.{}", pg_ident(schema.as_deref().unwrap_or("public")), pg_ident(&table));
+
+        let columns = client.query(
+            "SELECT a.attname::text, format_type(a.atttypid, a.atttypmod), NOT a.attnotnull, pg_get_expr(d.adbin, d.adrelid), \
+                    CASE WHEN pg_get_expr(d.adbin, d.adrelid) LIKE 'nextval(%' THEN 'auto_increment' END \
+             FROM pg_attribute a LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped ORDER BY a.attnum",
+            &[&qualified],
+        ).await.map_err(pg_err)?;
+
+        let indexes = client.query(
+            "SELECT i.relname::text, ix.indisunique, ix.indisprimary, \
+                    ARRAY(SELECT a.attname::text FROM unnest(ix.indkey::int2[]) WITH ORDINALITY k(attnum, ord) \
+                          JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = k.attnum ORDER BY k.ord) \
+             FROM pg_index ix JOIN pg_class i ON i.oid = ix.indexrelid \
+             WHERE ix.indrelid = $1::text::regclass ORDER BY i.relname",
+            &[&qualified],
+        ).await.map_err(pg_err)?;
+
+        Ok(TableStructure {
+            columns: columns.iter().map(|r| ColumnInfo {
+                name: r.get(0),
+                data_type: r.get(1),
+                nullable: r.get(2),
+                default: r.get(3),
+                extra: r.get(4),
+            }).collect(),
+            indexes: indexes.iter().map(|r| IndexInfo {
+                name: r.get(0),
+                unique: r.get(1),
+                primary: r.get(2),
+                columns: r.get(3),
+            }).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_semicolons_and_drops_empty_statements() {
+        assert_eq!(split_statements("; SELECT 1;;\nSELECT 2", true), vec!["SELECT 1", "SELECT 2"]);
+    }
+
+    #[test]
+    fn keeps_semicolons_inside_quotes() {
+        let sql = r#"SELECT 'a;b'; SELECT "c;d"; SELECT `e;f`"#;
+        assert_eq!(split_statements(sql, true), vec!["SELECT 'a;b'", r#"SELECT "c;d""#, "SELECT `e;f`"]);
+    }
+
+    #[test]
+    fn mysql_backslash_escapes_do_not_end_a_string() {
+        let sql = r"SELECT 'it\'s;'; SELECT 2";
+        assert_eq!(split_statements(sql, true), vec![r"SELECT 'it\'s;'", "SELECT 2"]);
+    }
+
+    #[test]
+    fn keeps_semicolons_inside_comments() {
+        let sql = "SELECT 1 -- a; b\n; /* ; */ SELECT 2; # c;\nSELECT 3";
+        assert_eq!(split_statements(sql, true), vec!["SELECT 1 -- a; b", "/* ; */ SELECT 2", "# c;\nSELECT 3"]);
+    }
+
+    #[test]
+    fn keeps_postgres_dollar_quoted_bodies_whole() {
+        let function = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql";
+        let sql = format!("{}; SELECT $1, f()", function);
+        assert_eq!(split_statements(&sql, false), vec![function, "SELECT $1, f()"]);
+    }
+
+    #[test]
+    fn reads_are_read_only() {
+        for sql in [
+            "SELECT * FROM users",
+            "  -- note\nshow tables",
+            "/* report */ (SELECT 1) UNION (SELECT 2)",
+            "EXPLAIN SELECT 1",
+            "WITH t AS (SELECT updated_at FROM x) SELECT * FROM t",
+        ] {
+            assert!(is_read_only(sql), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn writes_and_locking_reads_are_mutations() {
+        for sql in [
+            "UPDATE users SET name = 'x'",
+            "WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d",
+            "SELECT * FROM t INTO OUTFILE '/tmp/t.csv'",
+            "SELECT data INTO DUMPFILE '/tmp/blob' FROM t",
+            "SELECT * FROM t WHERE id = 1 FOR UPDATE",
+            "SELECT * FROM t FOR SHARE",
+            "SELECT * FROM t FOR NO KEY UPDATE",
+            "SELECT * FROM t LOCK IN SHARE MODE",
+        ] {
+            assert!(!is_read_only(sql), "{}", sql);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::db_backup::{drain_stderr, dump_command, import_command};
+use crate::db_client::{create_database_if_missing, running_instance};
+use crate::db_instances::DbInstance;
+
+const CHUNK_SIZE: usize = 256 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SqlTransferProgress {
+    pub instance: String,
+    pub database: String,
+    // Import: compressed bytes read from the file. Export: bytes written to it.
+    pub bytes: u64,
+    pub total: Option<u64>,
+    pub done: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Codec {
+    Plain,
+    Gzip,
+    Zstd,
+}
+
+// Counts bytes as the decoder pulls them, so progress matches the file size on disk
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+fn sniff_codec(file: &mut File) -> Result<Codec, String> {
+    let mut magic = [0u8; 4];
+    let n = file.read(&mut magic).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+
+    Ok(match &magic[..n] {
+        [0x1f, 0x8b, ..] => Codec::Gzip,
+        [0x28, 0xb5, 0x2f, 0xfd] => Codec::Zstd,
+        _ => Codec::Plain,
+    })
+}
+
+fn codec_for_export(path: &Path, compression: Option<&str>) -> Result<Codec, String> {
+    let name = compression.map(str::to_lowercase).unwrap_or_else(|| {
+        path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default()
+    });
+    match name.as_str() {
+        "gz" | "gzip" => Ok(Codec::Gzip),
+        "zst" | "zstd" => Ok(Codec::Zstd),
+        "" | "sql" | "none" | "plain" => Ok(Codec::Plain),
+        other => Err(format!("Unsupported compression: {}", other)),
+    }
+}
+
+// Pulls "at line N" out of mysql and "<stdin>:N:" out of psql error output
+fn error_line(stderr: &str) -> Option<u64> {
+    let digits = |s: &str| s.chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse().ok();
+    if let Some(pos) = stderr.find(" at line ") {
+        return digits(&stderr[pos + 9..]);
+    }
+    stderr.find("<stdin>:").and_then(|pos| digits(&stderr[pos + 8..]))
+}
+
+// Copies in chunks and reports the running count at most every PROGRESS_INTERVAL
+fn pump(reader: &mut dyn Read, writer: &mut dyn Write, mut progress: impl FnMut(u64)) -> io::Result<u64> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut written = 0u64;
+    let mut last = Instant::now();
+
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        written += n as u64;
+        if last.elapsed() >= PROGRESS_INTERVAL {
+            progress(written);
+            last = Instant::now();
+        }
+    }
+    Ok(written)
+}
+
+fn import_blocking(app: &AppHandle, instance: &DbInstance, database: &str, source: &Path) -> Result<u64, String> {
+    let mut file = File::open(source).map_err(|e| format!("Could not open {}: {}", source.display(), e))?;
+    let total = file.metadata().map(|m| m.len()).ok();
+    let codec = sniff_codec(&mut file)?;
+
+    let count = Arc::new(AtomicU64::new(0));
+    let counted = CountingReader { inner: file, count: count.clone() };
+    let mut reader: Box<dyn Read> = match codec {
+        Codec::Gzip => Box::new(MultiGzDecoder::new(counted)),
+        Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(counted).map_err(|e| e.to_string())?),
+        Codec::Plain => Box::new(counted),
+    };
+
+    let mut child = import_command(instance, database)?
+        .stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start the {} client: {}", instance.engine, e))?;
+    let stderr = drain_stderr(&mut child);
+
+    let emit = |bytes: u64, done: bool| {
+        let _ = app.emit("sql-import-progress", SqlTransferProgress {
+            instance: instance.name.clone(),
+            database: database.to_string(),
+            bytes,
+            total,
+            done,
+        });
+    };
+
+    let copied = {
+        let mut stdin = child.stdin.take().ok_or("Could not write to the import process")?;
+        pump(&mut reader, &mut stdin, |_| emit(count.load(Ordering::Relaxed), false))
+    };
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    let errors = stderr.join().unwrap_or_default();
+    if !status.success() {
+        let message = errors.trim();
+        return Err(match error_line(message) {
+            Some(line) => format!("Import into {} failed at line {}: {}", database, line, message),
+            None => format!("Import into {} failed: {}", database, message),
+        });
+    }
+    // A read error with a successful client means the dump itself is truncated or corrupt
+    let copied = copied.map_err(|e| format!("Could not read {}: {}", source.display(), e))?;
+
+    emit(count.load(Ordering::Relaxed), true);
+    Ok(copied)
+}
+
+fn export_blocking(app: &AppHandle, instance: &DbInstance, database: &str, target: &Path, codec: Codec) -> Result<u64, String> {
+    let mut child = dump_command(instance, database)?
+        .stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start the dump: {}", e))?;
+    let stderr = drain_stderr(&mut child);
+    let mut stdout = child.stdout.take().ok_or("Dump produced no output")?;
+
+    let emit = |bytes: u64, done: bool| {
+        let _ = app.emit("sql-export-progress", SqlTransferProgress {
+            instance: instance.name.clone(),
+            database: database.to_string(),
+            bytes,
+            total: None,
+            done,
+        });
+    };
+
+    let file = File::create(target).map_err(|e| format!("Could not create {}: {}", target.display(), e))?;
+    let result = (|| -> io::Result<u64> {
+        match codec {
+            Codec::Gzip => {
+                let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+                let n = pump(&mut stdout, &mut encoder, |b| emit(b, false))?;
+                encoder.finish()?.flush()?;
+                Ok(n)
+            }
+            Codec::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(BufWriter::new(file), 3)?;
+                let n = pump(&mut stdout, &mut encoder, |b| emit(b, false))?;
+                encoder.finish()?.flush()?;
+                Ok(n)
+            }
+            Codec::Plain => {
+                let mut writer = BufWriter::new(file);
+                let n = pump(&mut stdout, &mut writer, |b| emit(b, false))?;
+                writer.flush()?;
+                Ok(n)
+            }
+        }
+    })();
+
+    // A dump still writing into a pipe nobody reads would block forever
+    drop(stdout);
+    if result.is_err() {
+        let _ = child.kill();
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    let errors = stderr.join().unwrap_or_default();
+    if !status.success() || result.is_err() {
+        let _ = fs::remove_file(target);
+        return Err(match result {
+            Err(e) => format!("Export of {} failed: {}", database, e),
+            Ok(_) => format!("Export of {} failed: {}", database, errors.trim()),
+        });
+    }
+
+    let written = result.unwrap_or(0);
+    emit(written, true);
+    Ok(written)
+}
+
+#[tauri::command]
+pub async fn import_sql_file(app: AppHandle, instance: String, database: String, path: String) -> Result<String, String> {
+    let inst = running_instance(&instance)?;
+    let source = PathBuf::from(&path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+    create_database_if_missing(&inst, &database).await?;
+
+    println!("Importing {} into {}/{}", path, instance, database);
+    let db = database.clone();
+    let bytes = tauri::async_runtime::spawn_blocking(move || import_blocking(&app, &inst, &db, &source))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(format!("Imported {} bytes of SQL into {}", bytes, database))
+}
+
+#[tauri::command]
+pub async fn export_database(
+    app: AppHandle,
+    instance: String,
+    database: String,
+    path: String,
+    compression: Option<String>,
+) -> Result<String, String> {
+    let inst = running_instance(&instance)?;
+    let target = PathBuf::from(&path);
+    let codec = codec_for_export(&target, compression.as_deref())?;
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    println!("Exporting {}/{} to {}", instance, database, path);
+    let db = database.clone();
+    let bytes = tauri::async_runtime::spawn_blocking(move || export_blocking(&app, &inst, &db, &target, codec))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(format!("Exported {} ({} bytes of SQL) to {}", database, bytes, path))
+}
@@ -0,0 +1,347 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::credentials::generate_password;
+use crate::database::{initialize_mysql_datadir, initialize_pg_datadir, set_mysql_password, write_mysql_config};
+use crate::db_backup::{backup_one, client_command, drain_stderr, restore_from_file, BackupInfo};
+use crate::db_client::{create_database_if_missing, is_listening, is_mysql_family, list_databases};
+use crate::db_instances::{config_path, engine_and_version, find_tool, instance_dir, launch_spec, load_instance, save_instance, service_id, DbInstance};
+use crate::filesystem::get_home;
+use crate::process_manager::{launch_service, stop_service_by_id, ServiceState};
+
+fn services_dir() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("services"))
+}
+
+fn version_parts(version: &str) -> Vec<u32> {
+    version.split('.').map(|p| p.parse().unwrap_or(0)).collect()
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
+    for entry in fs::read_dir(from).map_err(|e| e.to_string())?.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target).map_err(|e| format!("Failed to copy {:?}: {}", entry.path(), e))?;
+        }
+    }
+    Ok(())
+}
+
+// Stops whatever runs on the instance's port (it may have been started under another service id)
+fn stop_instance_blocking(state: &ServiceState, instance: &DbInstance) -> Result<(), String> {
+    let ids: Vec<String> = state.launches.lock()
+        .map_err(|_| "Failed to lock state")?
+        .iter()
+        .filter(|(_, spec)| spec.port == Some(instance.port))
+        .map(|(id, _)| id.clone())
+        .collect();
+    for id in ids {
+        let _ = stop_service_by_id(state, &id);
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while is_listening(instance.port) {
+        if Instant::now() > deadline {
+            return Err(format!("{} is still listening on port {}. Stop it and try again.", instance.name, instance.port));
+        }
+        thread::sleep(Duration::from_millis(300));
+    }
+    Ok(())
+}
+
+// Stopping waits up to 30s for the port to close and launching waits for the server, so both run off the async runtime
+async fn stop_instance(app: &AppHandle, instance: &DbInstance) -> Result<(), String> {
+    let (app, inst) = (app.clone(), instance.clone());
+    tauri::async_runtime::spawn_blocking(move || stop_instance_blocking(&app.state::<ServiceState>(), &inst))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+async fn start_instance(app: &AppHandle, instance: &DbInstance) -> Result<(), String> {
+    let (app, inst) = (app.clone(), instance.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        let spec = launch_spec(&inst)?;
+        launch_service(&app.state::<ServiceState>(), service_id(&inst.name), spec).map(|_| ())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// mariadb-upgrade / mysql_upgrade fix system tables after the new server has started on old data.
+// MySQL 8.0.16+ does this itself on startup and no longer ships the tool.
+fn run_mysql_upgrade(instance: &DbInstance) -> Result<(), String> {
+    let service_dir = services_dir()?.join(&instance.version_folder);
+    if find_tool(&service_dir, &["mariadb-upgrade", "mysql_upgrade"]).is_none() {
+        return Ok(());
+    }
+    let mut command = client_command(instance, &["mariadb-upgrade", "mysql_upgrade"], "psql")?;
+    let output = command.stdin(Stdio::null()).output().map_err(|e| format!("Failed to run upgrade tool: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Upgrade tool failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+fn run_pg_upgrade(old: &DbInstance, new: &DbInstance, work_dir: &Path) -> Result<(), String> {
+    let old_bin = find_tool(&services_dir()?.join(&old.version_folder), &["postgres"])
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+        .ok_or(format!("Could not find postgres in {}", old.version_folder))?;
+    let new_service = services_dir()?.join(&new.version_folder);
+    let pg_upgrade = find_tool(&new_service, &["pg_upgrade"])
+        .ok_or(format!("pg_upgrade is not included in {}", new.version_folder))?;
+    let new_bin = pg_upgrade.parent().ok_or("Invalid pg_upgrade path")?.to_path_buf();
+
+    let mut command = std::process::Command::new(&pg_upgrade);
+    command.current_dir(work_dir)
+        .arg(format!("--old-bindir={}", old_bin.to_string_lossy()))
+        .arg(format!("--new-bindir={}", new_bin.to_string_lossy()))
+        .arg(format!("--old-datadir={}", old.data_dir))
+        .arg(format!("--new-datadir={}", new.data_dir))
+        .arg(format!("--username={}", old.user))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if !old.password.is_empty() { command.env("PGPASSWORD", &old.password); }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = command.spawn().map_err(|e| format!("Failed to start pg_upgrade: {}", e))?;
+    let stderr = drain_stderr(&mut child);
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let errors = stderr.join().unwrap_or_default();
+    if !output.status.success() {
+        // pg_upgrade explains most failures on stdout
+        return Err(format!("pg_upgrade failed: {} {}", String::from_utf8_lossy(&output.stdout).trim(), errors.trim()));
+    }
+    Ok(())
+}
+
+// Roles are cluster-wide and not part of per-database dumps
+fn dump_pg_globals(instance: &DbInstance, target: &Path) -> Result<(), String> {
+    let mut command = client_command(instance, &[], "pg_dumpall")?;
+    let output = command.arg("--globals-only").stdin(Stdio::null()).output()
+        .map_err(|e| format!("Failed to run pg_dumpall: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("pg_dumpall failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    fs::write(target, output.stdout).map_err(|e| e.to_string())
+}
+
+fn apply_pg_globals(instance: &DbInstance, source: &Path) -> Result<(), String> {
+    // No ON_ERROR_STOP: "role postgres already exists" is expected and harmless
+    let mut command = client_command(instance, &[], "psql")?;
+    let output = command.args(["-q", "-d", "postgres", "-f", &source.to_string_lossy()])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run psql: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Restoring roles failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+async fn restore_backups(instance: &DbInstance, backups: &[BackupInfo]) -> Result<(), String> {
+    for backup in backups {
+        create_database_if_missing(instance, &backup.database).await?;
+        let inst = instance.clone();
+        let database = backup.database.clone();
+        let source = PathBuf::from(&backup.file);
+        tauri::async_runtime::spawn_blocking(move || restore_from_file(&inst, &database, &source))
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+    Ok(())
+}
+
+// In place: start the new server on the existing files, then let mariadb-upgrade fix the system tables.
+// Fallback: a fresh data directory filled from the pre-upgrade dumps.
+async fn upgrade_mysql(app: &AppHandle, old: &DbInstance, new: &DbInstance, backups: &[BackupInfo]) -> Result<String, String> {
+    let emit = |msg: &str| { let _ = app.emit("db-upgrade-progress", format!("[{}] {}", old.name, msg)); };
+    let data_dir = PathBuf::from(&old.data_dir);
+    let rollback_dir = instance_dir(&old.name)?.join(format!("data.pre-upgrade-{}", old.version));
+
+    emit("Copying data directory for rollback...");
+    if rollback_dir.exists() { fs::remove_dir_all(&rollback_dir).map_err(|e| e.to_string())?; }
+    copy_dir(&data_dir, &rollback_dir)?;
+
+    write_mysql_config(new)?;
+    emit(&format!("Starting {} on the existing data...", new.version_folder));
+    let in_place = match start_instance(app, new).await {
+        Ok(()) => {
+            emit("Running mariadb-upgrade...");
+            let inst = new.clone();
+            tauri::async_runtime::spawn_blocking(move || run_mysql_upgrade(&inst))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+        Err(e) => Err(e),
+    };
+
+    match in_place {
+        Ok(()) => Ok(format!("Upgraded {} in place. The previous data is kept in {:?}", old.name, rollback_dir)),
+        Err(e) => {
+            emit(&format!("In-place upgrade failed ({}). Falling back to dump and restore...", e));
+            let _ = stop_instance(app, new).await;
+            fs::remove_dir_all(&data_dir).map_err(|e| e.to_string())?;
+            initialize_mysql_datadir(new)?;
+            start_instance(app, new).await?;
+
+            // A fresh data directory has a passwordless root
+            let mut fresh = new.clone();
+            fresh.password = String::new();
+            if !new.password.is_empty() {
+                set_mysql_password(&fresh, &new.password).await?;
+            }
+
+            emit("Restoring databases from the pre-upgrade backups...");
+            restore_backups(new, backups).await?;
+            Ok(format!(
+                "Upgraded {} by dump and restore. Database users other than {} must be recreated. The previous data is kept in {:?}",
+                old.name, new.user, rollback_dir
+            ))
+        }
+    }
+}
+
+async fn rollback_mysql(app: &AppHandle, old: &DbInstance, new: &DbInstance) -> Result<(), String> {
+    let _ = stop_instance(app, new).await;
+    let data_dir = PathBuf::from(&old.data_dir);
+    let rollback_dir = instance_dir(&old.name)?.join(format!("data.pre-upgrade-{}", old.version));
+    if rollback_dir.exists() {
+        if data_dir.exists() { fs::remove_dir_all(&data_dir).map_err(|e| e.to_string())?; }
+        fs::rename(&rollback_dir, &data_dir).map_err(|e| e.to_string())?;
+    }
+    write_mysql_config(old)
+}
+
+// pg_upgrade into a new data directory, leaving the old cluster untouched.
+// Fallback: a fresh cluster filled from the pre-upgrade dumps and role definitions.
+async fn upgrade_postgres(app: &AppHandle, old: &DbInstance, new: &DbInstance, backups: &[BackupInfo], globals: &Path) -> Result<String, String> {
+    let emit = |msg: &str| { let _ = app.emit("db-upgrade-progress", format!("[{}] {}", old.name, msg)); };
+    let work_dir = instance_dir(&old.name)?;
+    let password = if old.password.is_empty() { generate_password() } else { old.password.clone() };
+
+    emit(&format!("Initializing a {} cluster...", new.version_folder));
+    initialize_pg_datadir(new, &password)?;
+
+    emit("Running pg_upgrade...");
+    let (o, n, w) = (old.clone(), new.clone(), work_dir.clone());
+    let upgraded = tauri::async_runtime::spawn_blocking(move || run_pg_upgrade(&o, &n, &w))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Err(e) = upgraded {
+        emit(&format!("{}. Falling back to dump and restore...", e));
+        fs::remove_dir_all(&new.data_dir).map_err(|e| e.to_string())?;
+        initialize_pg_datadir(new, &password)?;
+        start_instance(app, new).await?;
+
+        emit("Restoring roles and databases from the pre-upgrade backups...");
+        apply_pg_globals(new, globals)?;
+        restore_backups(new, backups).await?;
+        return Ok(format!("Upgraded {} by dump and restore. The previous cluster is kept in {}", old.name, old.data_dir));
+    }
+
+    Ok(format!("Upgraded {} with pg_upgrade. The previous cluster is kept in {}", old.name, old.data_dir))
+}
+
+async fn rollback_postgres(app: &AppHandle, new: &DbInstance) -> Result<(), String> {
+    let _ = stop_instance(app, new).await;
+    if Path::new(&new.data_dir).exists() {
+        fs::remove_dir_all(&new.data_dir).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn upgrade_db_instance(app: AppHandle, from: String, to: String) -> Result<String, String> {
+    let emit = |msg: &str| { let _ = app.emit("db-upgrade-progress", format!("[{}] {}", from, msg)); };
+    let old = load_instance(&from)?;
+
+    let (engine, version) = engine_and_version(&to).ok_or(format!("Could not determine the version of {}", to))?;
+    if engine != old.engine {
+        return Err(format!("Cannot upgrade {} data with {}", old.engine, to));
+    }
+    if !services_dir()?.join(&to).exists() {
+        return Err(format!("Service {} not installed", to));
+    }
+    if version_parts(&version) <= version_parts(&old.version) {
+        return Err(format!("{} is not newer than the {} {} data in '{}'", to, old.engine, old.version, old.name));
+    }
+
+    let mut new = old.clone();
+    new.version_folder = to.clone();
+    new.version = version.clone();
+    if engine == "postgresql" {
+        new.data_dir = config_path(&instance_dir(&old.name)?.join(format!("data-{}", version)));
+        if Path::new(&new.data_dir).exists() {
+            return Err(format!("{} already exists. Remove it before upgrading again.", new.data_dir));
+        }
+    }
+
+    // Dumps need the old server running
+    let was_running = is_listening(old.port);
+    if !was_running {
+        emit("Starting the current server for the backup...");
+        start_instance(&app, &old).await?;
+    }
+
+    emit("Backing up all databases...");
+    let mut backups = Vec::new();
+    for database in list_databases(&old).await? {
+        emit(&format!("Backing up {}...", database));
+        backups.push(backup_one(&old, &database, "pre-upgrade").await?);
+    }
+    let globals = instance_dir(&old.name)?.join(format!("globals-{}.sql", old.version));
+    if engine == "postgresql" {
+        dump_pg_globals(&old, &globals)?;
+    }
+
+    emit("Stopping the current server...");
+    stop_instance(&app, &old).await?;
+
+    let result = if is_mysql_family(&engine) {
+        upgrade_mysql(&app, &old, &new, &backups).await
+    } else {
+        upgrade_postgres(&app, &old, &new, &backups, &globals).await
+    };
+
+    match result {
+        Ok(message) => {
+            save_instance(&new)?;
+            let running = is_listening(new.port);
+            if was_running && !running {
+                start_instance(&app, &new).await?;
+            } else if !was_running && running {
+                stop_instance(&app, &new).await?;
+            }
+            emit("Upgrade complete");
+            println!("{}", message);
+            Ok(message)
+        }
+        Err(e) => {
+            emit(&format!("Upgrade failed: {}. Rolling back...", e));
+            let rolled_back = if is_mysql_family(&engine) { rollback_mysql(&app, &old, &new).await } else { rollback_postgres(&app, &new).await };
+            if let Err(rollback_error) = rolled_back {
+                return Err(format!("Upgrade failed: {}. Rollback also failed: {}. Backups are in ~/.stackmanager/backups/{}", e, rollback_error, old.name));
+            }
+            if was_running {
+                let _ = start_instance(&app, &old).await;
+            }
+            emit("Rolled back to the previous version");
+            Err(format!("Upgrade failed and was rolled back: {}", e))
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use std::env;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::downloader::download_service;
+use crate::filesystem::get_home;
+use crate::network;
+use crate::process_manager::{launch_service, stop_service_by_id, LaunchSpec, ServiceState};
+use crate::proxy::ProxyState;
+
+// Valkey publishes Linux builds only; Redis itself publishes no binaries at all
+const VALKEY_VERSION: &str = "8.0.2";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ServicePort {
+    pub name: String,
+    pub port: u16,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DevServiceStatus {
+    pub kind: String,
+    pub installed: bool,
+    // Folder under services/, or the binary found on PATH
+    pub source: Option<String>,
+    pub running: bool,
+    pub healthy: bool,
+    pub ports: Vec<ServicePort>,
+    pub domain: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+const KINDS: [&str; 4] = ["redis", "valkey", "memcached", "mailpit"];
+
+fn stackmanager_dir() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager"))
+}
+
+fn check_kind(kind: &str) -> Result<(), String> {
+    if KINDS.contains(&kind) { Ok(()) } else { Err(format!("Unknown service: {}", kind)) }
+}
+
+fn service_id(kind: &str) -> String {
+    format!("svc_{}", kind)
+}
+
+fn binary_names(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "redis" => &["redis-server", "valkey-server"],
+        "valkey" => &["valkey-server", "redis-server"],
+        "memcached" => &["memcached"],
+        _ => &["mailpit"],
+    }
+}
+
+// (name, port) pairs; the first one is what readiness waits for
+fn default_ports(kind: &str) -> Vec<(&'static str, u16)> {
+    match kind {
+        "redis" | "valkey" => vec![("redis", 6379)],
+        "memcached" => vec![("memcached", 11211)],
+        _ => vec![("smtp", 1025), ("http", 8025)],
+    }
+}
+
+fn domain_for(kind: &str) -> Option<&'static str> {
+    if kind == "mailpit" { Some("mail.test") } else { None }
+}
+
+fn exe(name: &str) -> String {
+    if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() }
+}
+
+// Archives put binaries at the root, in bin/, or inside a single top-level folder
+fn find_binary(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    let mut roots = vec![dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(dir) {
+        roots.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+    }
+    for name in names {
+        for root in &roots {
+            for candidate in [root.join(exe(name)), root.join("bin").join(exe(name))] {
+                if candidate.is_file() { return Some(candidate); }
+            }
+        }
+    }
+    None
+}
+
+fn find_on_path(names: &[&str]) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    for name in names {
+        for dir in env::split_paths(&path) {
+            let candidate = dir.join(exe(name));
+            if candidate.is_file() { return Some(candidate); }
+        }
+    }
+    None
+}
+
+// Redis installs as Valkey on Linux, so each looks in the other's folders after its own
+fn folder_prefixes(kind: &str) -> Vec<String> {
+    match kind {
+        "redis" => vec!["redis-".into(), "valkey-".into()],
+        "valkey" => vec!["valkey-".into(), "redis-".into()],
+        _ => vec![format!("{}-", kind)],
+    }
+}
+
+// Newest installed folder for the service, else a system-wide install (Homebrew, apt)
+fn locate_binary(kind: &str) -> Option<(PathBuf, String)> {
+    let services = stackmanager_dir().ok()?.join("services");
+    let names: Vec<String> = fs::read_dir(&services).ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+
+    for prefix in folder_prefixes(kind) {
+        let mut folders: Vec<&String> = names.iter().filter(|n| n.to_lowercase().starts_with(&prefix)).collect();
+        folders.sort();
+        for folder in folders.iter().rev() {
+            if let Some(bin) = find_binary(&services.join(folder), binary_names(kind)) {
+                return Some((bin, folder.to_string()));
+            }
+        }
+    }
+    find_on_path(binary_names(kind)).map(|bin| (bin.clone(), bin.to_string_lossy().to_string()))
+}
+
+fn data_dir(kind: &str) -> Result<PathBuf, String> {
+    let dir = stackmanager_dir()?.join("data").join(kind);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn config_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+// Writes the default config on first start; later edits by the user are kept
+fn ensure_redis_config(dir: &Path, port: u16) -> Result<PathBuf, String> {
+    let conf = dir.join("redis.conf");
+    if !conf.exists() {
+        let content = [
+            format!("port {}", port),
+            "bind 127.0.0.1".to_string(),
+            "protected-mode yes".to_string(),
+            format!("dir \"{}\"", config_path(dir)),
+            "appendonly yes".to_string(),
+            "save 900 1".to_string(),
+            "save 60 1000".to_string(),
+            "maxmemory 256mb".to_string(),
+            "maxmemory-policy allkeys-lru".to_string(),
+        ].join("\n") + "\n";
+        fs::write(&conf, content).map_err(|e| e.to_string())?;
+    }
+    Ok(conf)
+}
+
+fn launch_spec_for(kind: &str) -> Result<LaunchSpec, String> {
+    let (bin, _) = locate_binary(kind).ok_or(format!("{} is not installed", kind))?;
+    let dir = data_dir(kind)?;
+    let ports = default_ports(kind);
+
+    let args = match kind {
+        "redis" | "valkey" => vec![config_path(&ensure_redis_config(&dir, ports[0].1)?)],
+        "memcached" => vec![
+            "-p".to_string(), ports[0].1.to_string(),
+            "-l".to_string(), "127.0.0.1".to_string(),
+            "-m".to_string(), "64".to_string(),
+        ],
+        _ => vec![
+            "--smtp".to_string(), format!("127.0.0.1:{}", ports[0].1),
+            "--listen".to_string(), format!("127.0.0.1:{}", ports[1].1),
+            "--database".to_string(), dir.join("mailpit.db").to_string_lossy().to_string(),
+        ],
+    };
+
+    Ok(LaunchSpec {
+        bin_path: bin.to_string_lossy().to_string(),
+        args,
+        cwd: Some(dir.to_string_lossy().to_string()),
+        env_paths: None,
+        port: Some(ports[0].1),
+    })
+}
+
+fn exchange(port: u16, request: &[u8]) -> Option<String> {
+    let addr = format!("127.0.0.1:{}", port).parse().ok()?;
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_millis(300)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_millis(500))).ok()?;
+    stream.write_all(request).ok()?;
+    let mut buf = [0u8; 256];
+    let n = stream.read(&mut buf).ok()?;
+    Some(String::from_utf8_lossy(&buf[..n]).to_string())
+}
+
+// An open port only means something is listening; these speak each protocol
+fn probe(kind: &str) -> bool {
+    let ports = default_ports(kind);
+    match kind {
+        "redis" | "valkey" => exchange(ports[0].1, b"PING\r\n").map(|r| r.starts_with("+PONG")).unwrap_or(false),
+        "memcached" => exchange(ports[0].1, b"version\r\n").map(|r| r.starts_with("VERSION")).unwrap_or(false),
+        _ => {
+            let smtp_ready = exchange(ports[0].1, b"").map(|r| r.starts_with("220")).unwrap_or(false);
+            let request = format!("GET /readyz HTTP/1.0\r\nHost: 127.0.0.1:{}\r\n\r\n", ports[1].1);
+            let http_ready = exchange(ports[1].1, request.as_bytes()).map(|r| r.contains(" 200 ")).unwrap_or(false);
+            smtp_ready && http_ready
+        }
+    }
+}
+
+// The probes block on sockets for up to a second each
+async fn probe_async(kind: &str) -> bool {
+    let kind = kind.to_string();
+    tauri::async_runtime::spawn_blocking(move || probe(&kind)).await.unwrap_or(false)
+}
+
+async fn latest_github_release(repo: &str) -> Result<GithubRelease, String> {
+    let client = network::build_client()?;
+    let url = network::rewrite_url(&format!("https://api.github.com/repos/{}/releases/latest", repo));
+    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to query {} releases: {}", repo, res.status()));
+    }
+    let body = res.text().await.map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+fn pick_asset(release: &GithubRelease, matches: impl Fn(&str) -> bool) -> Result<String, String> {
+    release.assets.iter()
+        .find(|a| matches(&a.name.to_lowercase()))
+        .map(|a| a.browser_download_url.clone())
+        .ok_or(format!("Release {} has no build for this platform", release.tag_name))
+}
+
+// (folder name, download url) for this platform
+async fn resolve_download(kind: &str) -> Result<(String, String), String> {
+    let arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => return Err(format!("Unsupported architecture: {}", other)),
+    };
+
+    match kind {
+        "mailpit" => {
+            let release = latest_github_release("axllent/mailpit").await?;
+            let os = if cfg!(target_os = "windows") { "windows" } else if cfg!(target_os = "macos") { "darwin" } else { "linux" };
+            let asset = format!("mailpit-{}-{}", os, arch);
+            let url = pick_asset(&release, |n| n.starts_with(&asset))?;
+            Ok((format!("mailpit-{}", release.tag_name.trim_start_matches('v')), url))
+        }
+        "redis" if cfg!(target_os = "windows") => {
+            let release = latest_github_release("redis-windows/redis-windows").await?;
+            let url = pick_asset(&release, |n| n.contains("windows-x64") && n.ends_with(".zip") && n.contains("msys2"))
+                .or_else(|_| pick_asset(&release, |n| n.contains("windows-x64") && n.ends_with(".zip")))?;
+            Ok((format!("redis-{}", release.tag_name.trim_start_matches('v')), url))
+        }
+        "memcached" if cfg!(target_os = "windows") => {
+            let release = latest_github_release("jefyt/memcached-windows").await?;
+            let url = pick_asset(&release, |n| n.contains("win64") && n.ends_with(".zip"))?;
+            let version = release.tag_name.split('_').next().unwrap_or(&release.tag_name).to_string();
+            Ok((format!("memcached-{}", version), url))
+        }
+        "redis" | "valkey" if cfg!(target_os = "linux") => {
+            let arch = if arch == "amd64" { "x86_64" } else { arch };
+            let url = format!("https://download.valkey.io/releases/valkey-{}-jammy-{}.tar.gz", VALKEY_VERSION, arch);
+            Ok((format!("valkey-{}", VALKEY_VERSION), url))
+        }
+        _ => Err(format!(
+            "No prebuilt {} is available for this platform. Install it with your package manager; StackManager will use it from PATH.",
+            kind
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn install_dev_service(kind: String) -> Result<String, String> {
+    check_kind(&kind)?;
+    let (folder, url) = resolve_download(&kind).await?;
+    if stackmanager_dir()?.join("services").join(&folder).exists() {
+        return Ok(format!("{} is already installed", folder));
+    }
+    download_service(folder.clone(), url).await?;
+
+    // tar.gz archives from GitHub keep the executable bit; zips on Unix don't
+    #[cfg(unix)]
+    if let Some((bin, _)) = locate_binary(&kind) {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&bin, fs::Permissions::from_mode(0o755));
+    }
+    Ok(folder)
+}
+
+#[tauri::command]
+pub async fn start_dev_service(
+    state: State<'_, ServiceState>,
+    proxy: State<'_, Arc<ProxyState>>,
+    kind: String,
+) -> Result<String, String> {
+    check_kind(&kind)?;
+    let result = launch_service(&state, service_id(&kind), launch_spec_for(&kind)?)?;
+
+    // The port opens a moment before the service answers
+    let mut ready = false;
+    for _ in 0..20 {
+        if probe_async(&kind).await {
+            ready = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    if !ready {
+        let _ = stop_service_by_id(&state, &service_id(&kind));
+        return Err(format!("{} started but did not answer on port {}; it was stopped", kind, default_ports(&kind)[0].1));
+    }
+
+    if let Some(domain) = domain_for(&kind) {
+        let http_port = default_ports(&kind).iter().find(|(n, _)| *n == "http").map(|(_, p)| *p);
+        if let Some(port) = http_port {
+            proxy.routes.lock().map_err(|_| "Failed to lock proxy routes")?.insert(domain.to_string(), port);
+        }
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn stop_dev_service(state: State<ServiceState>, proxy: State<Arc<ProxyState>>, kind: String) -> Result<String, String> {
+    check_kind(&kind)?;
+    if let Some(domain) = domain_for(&kind) {
+        if let Ok(mut routes) = proxy.routes.lock() {
+            routes.remove(domain);
+        }
+    }
+    stop_service_by_id(&state, &service_id(&kind))
+}
+
+#[tauri::command]
+pub async fn get_dev_services_status(state: State<'_, ServiceState>) -> Result<Vec<DevServiceStatus>, String> {
+    let pids = state.pids.lock().map(|p| p.clone()).unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || KINDS.iter().map(|kind| {
+        let located = locate_binary(kind);
+        let running = pids.contains_key(&service_id(kind));
+        let ports: Vec<ServicePort> = default_ports(kind).into_iter()
+            .map(|(name, port)| ServicePort { name: name.to_string(), port })
+            .collect();
+        let url = match *kind {
+            "mailpit" => Some(format!("http://{}", domain_for(kind).unwrap_or("127.0.0.1:8025"))),
+            "memcached" => Some(format!("memcached://127.0.0.1:{}", ports[0].port)),
+            _ => Some(format!("redis://127.0.0.1:{}", ports[0].port)),
+        };
+
+        DevServiceStatus {
+            kind: kind.to_string(),
+            installed: located.is_some(),
+            source: located.map(|(_, source)| source),
+            running,
+            healthy: running && probe(kind),
+            ports,
+            domain: domain_for(kind).map(str::to_string),
+            url,
+        }
+    }).collect())
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -1,51 +1,138 @@
 use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::env;
+use flate2::read::GzDecoder;
 use futures_util::StreamExt;
 use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use zip::ZipArchive;
+use crate::cache;
+use crate::filesystem::get_home;
+use crate::network;
 
 fn get_stackmanager_root() -> Option<PathBuf> {
-    #[cfg(target_os = "windows")]
-    let home = env::var("USERPROFILE").ok().map(PathBuf::from)?;
-    #[cfg(not(target_os = "windows"))]
-    let home = env::var("HOME").ok().map(PathBuf::from)?;
-
+    let home = get_home()?;
     Some(home.join(".stackmanager"))
 }
 
-#[tauri::command]
-pub async fn download_service(name: String, url: String) -> Result<String, String> {
-    let root = get_stackmanager_root().ok_or("Could not find home directory")?;
-    let target_path = root.join("services");
-    
-    if !target_path.exists() { fs::create_dir_all(&target_path).map_err(|e| e.to_string())?; }
-
-    let service_folder = target_path.join(&name);
-    let zip_path = target_path.join(format!("{}.zip", name));
-
-    let client = Client::builder().user_agent("StackManager/1.0").build().map_err(|e| e.to_string())?;
-
-    println!("Downloading Service: {}", url);
-    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+// Returns a local copy of `url`, downloading it into the shared cache only on a miss.
+pub async fn fetch_cached(client: &Client, url: &str) -> Result<PathBuf, String> {
+    if let Some(hit) = cache::lookup(url) {
+        println!("Using cached download: {}", url);
+        return Ok(hit);
+    }
 
+    let source = network::rewrite_url(url);
+    println!("Downloading: {}", source);
+    let res = client.get(&source).send().await.map_err(|e| e.to_string())?;
     if !res.status().is_success() { return Err(format!("Failed to connect: {}", res.status())); }
 
+    let tmp_path = cache::temp_path()?;
+    let mut hasher = Sha256::new();
     {
         let mut stream = res.bytes_stream();
-        let mut file = File::create(&zip_path).map_err(|e| e.to_string())?;
+        let mut file = File::create(&tmp_path).map_err(|e| e.to_string())?;
         while let Some(item) = stream.next().await {
             let chunk = item.map_err(|e| e.to_string())?;
+            hasher.update(&chunk);
             file.write_all(&chunk).map_err(|e| e.to_string())?;
         }
     }
 
+    let file_name = url.rsplit('/').next().unwrap_or("download");
+    cache::store(url, file_name, &tmp_path, &cache::to_hex(&hasher.finalize()))
+}
+
+// Copies a cached single-file download (e.g. a .phar) to `dest`, via a .part file so an interrupted copy is never picked up
+pub async fn fetch_to(url: &str, dest: &Path) -> Result<(), String> {
+    let client = network::build_client()?;
+    let cached = fetch_cached(&client, url).await?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let partial = dest.with_file_name(format!("{}.part", dest.file_name().unwrap_or_default().to_string_lossy()));
+    fs::copy(&cached, &partial).map_err(|e| e.to_string())?;
+    fs::rename(&partial, dest).map_err(|e| e.to_string())
+}
+
+// Detects zip / tar.gz / tar by magic bytes, so cached blobs don't need their extension.
+pub fn extract_archive(archive_path: &Path, dest: &Path) -> Result<(), String> {
+    let mut header = [0u8; 262];
+    let read = File::open(archive_path)
+        .and_then(|mut f| f.read(&mut header))
+        .map_err(|e| e.to_string())?;
+
+    let file = File::open(archive_path).map_err(|e| e.to_string())?;
+
+    if read >= 4 && header[..4] == *b"PK\x03\x04" {
+        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+        archive.extract(dest).map_err(|e| e.to_string())?;
+    } else if read >= 2 && header[..2] == [0x1f, 0x8b] {
+        tar::Archive::new(GzDecoder::new(file)).unpack(dest).map_err(|e| e.to_string())?;
+    } else if read >= 262 && header[257..262] == *b"ustar" {
+        tar::Archive::new(file).unpack(dest).map_err(|e| e.to_string())?;
+    } else {
+        return Err(format!("Unsupported archive format: {:?}", archive_path));
+    }
+
+    Ok(())
+}
+
+fn service_name_from_archive(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let lower = file_name.to_lowercase();
+    for ext in [".tar.gz", ".tgz", ".tar", ".zip"] {
+        if lower.ends_with(ext) {
+            return Some(file_name[..file_name.len() - ext.len()].to_string());
+        }
+    }
+    Some(file_name)
+}
+
+#[tauri::command]
+pub fn install_service_from_file(path: String, name: Option<String>) -> Result<String, String> {
+    let root = get_stackmanager_root().ok_or("Could not find home directory")?;
+    let target_path = root.join("services");
+    if !target_path.exists() { fs::create_dir_all(&target_path).map_err(|e| e.to_string())?; }
+
+    let archive_path = PathBuf::from(&path);
+    if !archive_path.is_file() { return Err(format!("Archive not found: {}", path)); }
+
+    let name = match name {
+        Some(n) if !n.trim().is_empty() => n,
+        _ => service_name_from_archive(&archive_path).ok_or("Could not derive service name from archive")?,
+    };
+
+    if name.contains(['/', '\\']) || name.starts_with('.') { return Err("Invalid service name".to_string()); }
+
+    let service_folder = target_path.join(&name);
+    if service_folder.exists() { return Err(format!("{} is already installed", name)); }
+
+    println!("Installing {} from {:?}", name, archive_path);
+    if let Err(e) = extract_archive(&archive_path, &service_folder) {
+        fs::remove_dir_all(&service_folder).ok();
+        return Err(e);
+    }
+
+    Ok(format!("Installed {}", name))
+}
+
+#[tauri::command]
+pub async fn download_service(name: String, url: String) -> Result<String, String> {
+    let root = get_stackmanager_root().ok_or("Could not find home directory")?;
+    let target_path = root.join("services");
+    
+    if !target_path.exists() { fs::create_dir_all(&target_path).map_err(|e| e.to_string())?; }
+
+    let service_folder = target_path.join(&name);
+
+    let client = network::build_client()?;
+    let archive_path = fetch_cached(&client, &url).await?;
+
     println!("Unzipping to: {:?}", service_folder);
-    let file = File::open(&zip_path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-    archive.extract(&service_folder).map_err(|e| e.to_string())?;
-    fs::remove_file(zip_path).map_err(|e| e.to_string())?;
+    extract_archive(&archive_path, &service_folder)?;
 
     Ok(format!("Downloaded {}", name))
 }
@@ -62,20 +149,10 @@ pub async fn install_adminer_file(file_name: String, url: String) -> Result<Stri
 
     let target_file = target_dir.join(&file_name);
 
-    let client = Client::builder().user_agent("StackManager/1.0").build().map_err(|e| e.to_string())?;
-
-    println!("Downloading Adminer File: {}", url);
-    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() { return Err(format!("Failed: {}", res.status())); }
-
-    let mut stream = res.bytes_stream();
-    let mut file = File::create(&target_file).map_err(|e| e.to_string())?;
+    let client = network::build_client()?;
 
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
-    }
+    let cached = fetch_cached(&client, &url).await?;
+    fs::copy(&cached, &target_file).map_err(|e| e.to_string())?;
 
     Ok(format!("Saved to {:?}", target_file))
 }
@@ -90,31 +167,16 @@ pub async fn download_postgresql() -> Result<String, String> {
     let url = "https://get.enterprisedb.com/postgresql/postgresql-16.2-1-windows-x64-binaries.zip";
     
     let service_folder = target_path.join(folder_name);
-    let zip_path = target_path.join(format!("{}.zip", folder_name));
 
     if service_folder.exists() {
         return Ok("PostgreSQL already installed".to_string());
     }
 
-    let client = Client::builder().user_agent("StackManager/1.0").build().map_err(|e| e.to_string())?;
-    
-    println!("Downloading PostgreSQL: {}", url);
-    let res = client.get(url).send().await.map_err(|e| e.to_string())?;
-    if !res.status().is_success() { return Err(format!("Failed to connect: {}", res.status())); }
-
-    let mut stream = res.bytes_stream();
-    let mut file = File::create(&zip_path).map_err(|e| e.to_string())?;
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
-    }
+    let client = network::build_client()?;
+    let archive_path = fetch_cached(&client, url).await?;
 
     println!("Unzipping PostgreSQL...");
-    let file = File::open(&zip_path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-    archive.extract(&service_folder).map_err(|e| e.to_string())?;
-    
-    fs::remove_file(zip_path).map_err(|e| e.to_string())?;
+    extract_archive(&archive_path, &service_folder)?;
 
     Ok(folder_name.to_string())
 }
@@ -123,7 +185,7 @@ pub async fn download_postgresql() -> Result<String, String> {
 pub async fn download_php_robust(version: String) -> Result<String, String> {
     let root = get_stackmanager_root().ok_or("Could not find home directory")?;
     let target_path = root.join("services");
-    let client = Client::builder().user_agent("StackManager/1.0").build().map_err(|e| e.to_string())?;
+    let client = network::build_client()?;
 
     let parts: Vec<&str> = version.split('.').collect();
     let major: i32 = parts[0].parse().unwrap_or(8);
@@ -144,15 +206,17 @@ pub async fn download_php_robust(version: String) -> Result<String, String> {
         format!("https://windows.php.net/downloads/releases/archives/php-{}-Win32-vs16-x64.zip", version),
     ];
 
-    let mut valid_url = None;
-
-    for url in candidates {
-        println!("Probing: {}", url);
-        let resp = client.head(&url).send().await;
-        if let Ok(r) = resp {
-            if r.status().is_success() {
-                valid_url = Some(url);
-                break;
+    let mut valid_url = candidates.iter().find(|url| cache::lookup(url).is_some()).cloned();
+
+    if valid_url.is_none() {
+        for url in candidates {
+            println!("Probing: {}", url);
+            let resp = client.head(network::rewrite_url(&url)).send().await;
+            if let Ok(r) = resp {
+                if r.status().is_success() {
+                    valid_url = Some(url);
+                    break;
+                }
             }
         }
     }
@@ -160,22 +224,123 @@ pub async fn download_php_robust(version: String) -> Result<String, String> {
     let download_url = valid_url.ok_or(format!("Could not find a download for PHP {}. Try a different version.", version))?;
 
     let service_folder = target_path.join(&filename);
-    let zip_path = target_path.join(&zip_name);
+    let archive_path = fetch_cached(&client, &download_url).await?;
+    extract_archive(&archive_path, &service_folder)?;
+
+    Ok(filename)
+}
+
+const NODE_DIST: &str = "https://nodejs.org/dist";
 
-    println!("Downloading from: {}", download_url);
-    let res = client.get(download_url).send().await.map_err(|e| e.to_string())?;
-    let mut stream = res.bytes_stream();
-    let mut file = File::create(&zip_path).map_err(|e| e.to_string())?;
+#[derive(Deserialize)]
+struct NodeRelease {
+    version: String,
+    lts: serde_json::Value,
+    files: Vec<String>,
+}
 
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
+// (index.json "files" key, archive suffix) for the build matching this machine
+fn node_platform() -> Result<(String, String), String> {
+    let arch = match env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "x86" => "x86",
+        other => return Err(format!("Unsupported architecture for Node.js: {}", other)),
+    };
+
+    if cfg!(target_os = "windows") {
+        Ok((format!("win-{}-zip", arch), format!("win-{}.zip", arch)))
+    } else if cfg!(target_os = "macos") {
+        Ok((format!("osx-{}-tar", arch), format!("darwin-{}.tar.gz", arch)))
+    } else {
+        Ok((format!("linux-{}", arch), format!("linux-{}.tar.gz", arch)))
     }
+}
 
-    let file = File::open(&zip_path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-    archive.extract(&service_folder).map_err(|e| e.to_string())?;
-    fs::remove_file(zip_path).map_err(|e| e.to_string())?;
+// Accepts "20", "20.11", "v20.11.1", "lts", "lts/*", "lts/iron", "latest"
+fn resolve_node_release<'a>(releases: &'a [NodeRelease], spec: &str, files_key: &str) -> Option<&'a NodeRelease> {
+    let spec = spec.trim().trim_start_matches('v').to_lowercase();
+
+    // index.json is ordered newest first, so the first match is the latest one
+    releases.iter()
+        .filter(|r| r.files.iter().any(|f| f == files_key))
+        .find(|r| {
+            let lts_name = r.lts.as_str().map(|s| s.to_lowercase());
+            let number = r.version.trim_start_matches('v');
+            match spec.as_str() {
+                "latest" | "current" | "node" => true,
+                "lts" | "lts/*" => lts_name.is_some(),
+                s if s.starts_with("lts/") => lts_name.as_deref() == Some(&s[4..]),
+                s => number == s || number.starts_with(&format!("{}.", s)),
+            }
+        })
+}
 
-    Ok(filename)
-}
\ No newline at end of file
+fn node_archive_url(version: &str, suffix: &str) -> String {
+    format!("{}/v{}/node-v{}-{}", NODE_DIST, version, version, suffix)
+}
+
+async fn fetch_node_releases(client: &Client) -> Result<Vec<NodeRelease>, String> {
+    let index_url = network::rewrite_url(&format!("{}/index.json", NODE_DIST));
+    println!("Fetching Node release index: {}", index_url);
+    let res = client.get(&index_url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() { return Err(format!("Failed to fetch Node release index: {}", res.status())); }
+    let body = res.text().await.map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+// Every release with a build for this machine, newest first, as bare versions
+pub async fn node_release_versions(client: &Client) -> Result<Vec<String>, String> {
+    let (files_key, _) = node_platform()?;
+    let releases = fetch_node_releases(client).await?;
+    Ok(releases.iter()
+        .filter(|r| r.files.contains(&files_key))
+        .map(|r| r.version.trim_start_matches('v').to_string())
+        .collect())
+}
+
+// Resolves an alias against the release index; returns a bare version like "20.11.1"
+pub async fn resolve_node_version(client: &Client, spec: &str) -> Result<String, String> {
+    let (files_key, _) = node_platform()?;
+    let releases = fetch_node_releases(client).await?;
+
+    let release = resolve_node_release(&releases, spec, &files_key)
+        .ok_or(format!("No Node.js release matches '{}' for {}", spec, files_key))?;
+    Ok(release.version.trim_start_matches('v').to_string())
+}
+
+#[tauri::command]
+pub async fn download_node(version: String) -> Result<String, String> {
+    let root = get_stackmanager_root().ok_or("Could not find home directory")?;
+    let target_path = root.join("services");
+    if !target_path.exists() { fs::create_dir_all(&target_path).map_err(|e| e.to_string())?; }
+
+    let client = network::build_client()?;
+    let (_, suffix) = node_platform()?;
+
+    let exact = version.trim_start_matches('v');
+    let is_exact = exact.split('.').filter(|p| p.parse::<u32>().is_ok()).count() == 3;
+
+    // Exact versions that are already cached install without touching the release index
+    let resolved = if is_exact && cache::lookup(&node_archive_url(exact, &suffix)).is_some() {
+        exact.to_string()
+    } else {
+        resolve_node_version(&client, &version).await?
+    };
+
+    let folder_name = format!("node-v{}", resolved);
+    let service_folder = target_path.join(&folder_name);
+    if service_folder.exists() {
+        return Ok(folder_name);
+    }
+
+    let archive_path = fetch_cached(&client, &node_archive_url(&resolved, &suffix)).await?;
+
+    println!("Extracting Node.js {}...", resolved);
+    if let Err(e) = extract_archive(&archive_path, &service_folder) {
+        fs::remove_dir_all(&service_folder).ok();
+        return Err(e);
+    }
+
+    Ok(folder_name)
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::Path;
+
+fn format_env_value(value: &str) -> String {
+    let needs_quotes = value.is_empty() || value.contains(|c: char| c.is_whitespace() || matches!(c, '#' | '"' | '\'' | '$' | '='));
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn line_key(line: &str) -> Option<(bool, &str)> {
+    let trimmed = line.trim_start();
+    let (commented, rest) = match trimmed.strip_prefix('#') {
+        Some(r) => (true, r.trim_start()),
+        None => (false, trimmed),
+    };
+    let rest = rest.strip_prefix("export ").unwrap_or(rest);
+    let (key, _) = rest.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') { return None; }
+    Some((commented, key))
+}
+
+// Sets keys in a .env file in place, uncommenting template lines like "# DB_HOST=127.0.0.1"
+// that Laravel ships, and appends anything that isn't there yet.
+// A missing .env is seeded from .env.example when the project has one.
+pub fn update_env_file(project_path: &Path, values: &[(&str, String)]) -> Result<(), String> {
+    let env_path = project_path.join(".env");
+    let example = project_path.join(".env.example");
+
+    let content = if env_path.exists() {
+        fs::read_to_string(&env_path).map_err(|e| e.to_string())?
+    } else if example.exists() {
+        fs::read_to_string(&example).map_err(|e| e.to_string())?
+    } else {
+        String::new()
+    };
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    for (key, value) in values {
+        let new_line = format!("{}={}", key, format_env_value(value));
+        let active = lines.iter().rposition(|l| line_key(l) == Some((false, key)));
+        let commented = lines.iter().position(|l| line_key(l) == Some((true, key)));
+
+        match active.or(commented) {
+            Some(index) => lines[index] = new_line,
+            None => lines.push(new_line),
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    fs::write(&env_path, output).map_err(|e| format!("Failed to write {:?}: {}", env_path, e))
+}
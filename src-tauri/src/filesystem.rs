@@ -2,9 +2,17 @@ use std::fs;
 use std::path::PathBuf;
 use std::env;
 use std::collections::HashMap;
+use crate::php_extensions::set_extension_enabled_in;
+use crate::php_ini::IniFile;
+use crate::db_provision::drop_databases_for;
 
-// Helper function to get home directory safely on any OS
-fn get_home_dir() -> Option<PathBuf> {
+const DEFAULT_PHP_EXTENSIONS: [&str; 12] = [
+    "curl", "fileinfo", "mbstring", "openssl", "pdo_mysql", "mysqli",
+    "gd", "gd2", "intl", "zip", "pdo_pgsql", "pgsql",
+];
+
+// Home directory on any OS; everything under ~/.stackmanager is resolved from here
+pub fn get_home() -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     {
         env::var("USERPROFILE").ok().map(PathBuf::from)
@@ -43,7 +51,7 @@ pub fn open_file_in_editor(file_path: String) -> Result<(), String> {
 
 #[tauri::command]
 pub fn init_environment() -> Result<String, String> {
-    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let home = get_home().ok_or("Could not find home directory")?;
     let root_path = home.join(".stackmanager");
     let services_path = root_path.join("services");
     let bin_path = root_path.join("bin");
@@ -57,7 +65,7 @@ pub fn init_environment() -> Result<String, String> {
 
 #[tauri::command]
 pub fn get_services() -> Result<Vec<String>, String> {
-    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let home = get_home().ok_or("Could not find home directory")?;
     let services_path = home.join(".stackmanager").join("services");
 
     if !services_path.exists() { return Ok(vec![]); }
@@ -87,7 +95,7 @@ pub fn get_node_versions() -> Result<Vec<String>, String> {
 
 #[tauri::command]
 pub fn get_service_bin_path(service_name: String) -> Result<String, String> {
-    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let home = get_home().ok_or("Could not find home directory")?;
     let services_path = home.join(".stackmanager").join("services");
     let base_path = services_path.join(&service_name);
 
@@ -114,40 +122,44 @@ pub fn get_service_bin_path(service_name: String) -> Result<String, String> {
 
 #[tauri::command]
 pub fn get_node_path(service_name: String) -> Result<String, String> {
-    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let home = get_home().ok_or("Could not find home directory")?;
     let base_path = home.join(".stackmanager").join("services").join(&service_name);
 
     if !base_path.exists() { return Err("Node version not installed".to_string()); }
 
-    if base_path.join("node.exe").exists() {
-        return Ok(base_path.to_string_lossy().to_string());
-    }
+    // Windows zips keep node.exe at the top level, Unix tarballs put it under bin/
+    let node_exe = if cfg!(target_os = "windows") { "node.exe" } else { "node" };
+    let mut candidates = vec![base_path.clone(), base_path.join("bin")];
 
     if let Ok(entries) = fs::read_dir(&base_path) {
         for entry in entries.flatten() {
             if let Ok(ft) = entry.file_type() {
                 if ft.is_dir() {
-                    let nested_node = entry.path().join("node.exe");
-                    if nested_node.exists() {
-                        return Ok(entry.path().to_string_lossy().to_string());
-                    }
+                    candidates.push(entry.path());
+                    candidates.push(entry.path().join("bin"));
                 }
             }
         }
     }
 
-    Err("Could not find node.exe in service folder".to_string())
+    for dir in candidates {
+        if dir.join(node_exe).is_file() {
+            return Ok(dir.to_string_lossy().to_string());
+        }
+    }
+
+    Err(format!("Could not find {} in service folder", node_exe))
 }
 
 #[tauri::command]
 pub fn get_user_home() -> Result<String, String> {
-    let home = get_home_dir().ok_or("Home not found")?;
+    let home = get_home().ok_or("Home not found")?;
     Ok(home.to_string_lossy().to_string())
 }
 
 #[tauri::command]
 pub fn delete_service_folder(folder_name: String) -> Result<String, String> {
-    let home = get_home_dir().ok_or("Could not find home directory")?;
+    let home = get_home().ok_or("Could not find home directory")?;
     let services_path = home.join(".stackmanager").join("services");
     let target_path = services_path.join(&folder_name);
 
@@ -159,7 +171,7 @@ pub fn delete_service_folder(folder_name: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn delete_project_dir(path: String) -> Result<String, String> {
+pub async fn delete_project_dir(path: String) -> Result<String, String> {
     let path_buf = PathBuf::from(&path);
     
     if !path_buf.exists() {
@@ -175,7 +187,12 @@ pub fn delete_project_dir(path: String) -> Result<String, String> {
     }
     
     fs::remove_dir_all(&path_buf).map_err(|e| e.to_string())?;
-    Ok("Project files deleted successfully".to_string())
+
+    // Databases go only once the files are gone, so a failed delete leaves the project intact
+    match drop_databases_for(&path).await.err() {
+        Some(w) => Ok(format!("Project files deleted. Some databases were not dropped: {}", w)),
+        None => Ok("Project files deleted successfully".to_string()),
+    }
 }
 
 #[tauri::command]
@@ -188,29 +205,13 @@ pub fn check_projects_status(paths: Vec<String>) -> HashMap<String, bool> {
     status
 }
 
-#[tauri::command]
-pub fn detect_framework(path: String) -> String {
-    let p = PathBuf::from(&path);
-    
-    // PHP Frameworks
-    if p.join("artisan").exists() { return "laravel".to_string(); }
-    if p.join("wp-config.php").exists() || p.join("wp-settings.php").exists() { return "wordpress".to_string(); }
-    if p.join("bin").join("console").exists() { return "symfony".to_string(); }
-
-    // Node Frameworks
-    if p.join("svelte.config.js").exists() { return "svelte".to_string(); }
-    if p.join("vite.config.js").exists() || p.join("vite.config.ts").exists() { return "react".to_string(); } // Generic Vite/React
-    if p.join("package.json").exists() { return "node".to_string(); } // Generic Node
-
-    "custom".to_string()
-}
-
 #[tauri::command]
 pub fn prepare_php_ini(bin_path_dir: String) -> Result<String, String> {
     let dir = PathBuf::from(&bin_path_dir);
     let ini_path = dir.join("php.ini");
+    let created = !ini_path.exists();
     
-    if !ini_path.exists() {
+    if created {
         let dev_ini = dir.join("php.ini-development");
         let prod_ini = dir.join("php.ini-production");
         
@@ -223,51 +224,25 @@ pub fn prepare_php_ini(bin_path_dir: String) -> Result<String, String> {
         }
     }
 
-    let content = fs::read_to_string(&ini_path).map_err(|e| e.to_string())?;
-    let mut new_lines = Vec::new();
-    let mut modified = false;
+    let mut ini = IniFile::load(&ini_path)?;
 
-    for line in content.lines() {
-        let trimmed = line.trim();
-        
-        if trimmed.starts_with(";extension=curl") || 
-           trimmed.starts_with(";extension=fileinfo") || 
-           trimmed.starts_with(";extension=mbstring") || 
-           trimmed.starts_with(";extension=openssl") || 
-           trimmed.starts_with(";extension=pdo_mysql") || 
-           trimmed.starts_with(";extension=mysqli") ||
-           trimmed.starts_with(";extension=gd") ||
-           trimmed.starts_with(";extension=intl") || 
-           trimmed.starts_with(";extension=zip") ||
-           trimmed.starts_with(";extension=pdo_pgsql") || 
-           trimmed.starts_with(";extension=pgsql") {
-            
-            new_lines.push(trimmed.replacen(";", "", 1));
-            modified = true;
-        } 
-        else if trimmed.starts_with(";extension_dir = \"ext\"") || trimmed.starts_with("; extension_dir = \"ext\"") {
-             new_lines.push("extension_dir = \"ext\"".to_string());
-             modified = true;
-        }
-        else if trimmed.starts_with("post_max_size =") {
-            new_lines.push("post_max_size = 64M".to_string());
-            modified = true;
-        }
-        else if trimmed.starts_with("upload_max_filesize =") {
-            new_lines.push("upload_max_filesize = 64M".to_string());
-            modified = true;
-        }
-        else if trimmed.starts_with("memory_limit =") {
-            new_lines.push("memory_limit = 512M".to_string());
-            modified = true;
-        }
-        else {
-            new_lines.push(line.to_string());
-        }
+    for ext in DEFAULT_PHP_EXTENSIONS {
+        set_extension_enabled_in(&mut ini, ext, true, false);
+    }
+
+    if ini.get("extension_dir").is_none() {
+        ini.set("extension_dir", "ext");
+    }
+
+    // Limits are only seeded on a fresh copy so later edits by the user are left alone
+    if created {
+        ini.set("post_max_size", "64M");
+        ini.set("upload_max_filesize", "64M");
+        ini.set("memory_limit", "512M");
     }
 
-    if modified {
-        fs::write(&ini_path, new_lines.join("\n")).map_err(|e| e.to_string())?;
+    if ini.is_modified() {
+        ini.save(&ini_path)?;
         Ok("Configured php.ini with Laravel & Postgres extensions".to_string())
     } else {
         Ok("php.ini already configured".to_string())
@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use serde_json::Value;
+use crate::tasks::{default_tasks, ProjectTask};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FrameworkInfo {
+    pub framework: String,
+    pub version: Option<String>,
+    // composer.json require.php, e.g. "^8.2"
+    pub php: Option<String>,
+    // package.json engines.node, e.g. ">=18.17"
+    pub node: Option<String>,
+    // "npm", "pnpm", "yarn" or "bun"; None without a package.json
+    pub package_manager: Option<String>,
+    // {port} is replaced with the project's port when started
+    pub start_command: Option<String>,
+    // Relative to the project, "." for the project root; None for Node apps
+    pub docroot: Option<String>,
+    pub tasks: Vec<ProjectTask>,
+}
+
+pub fn read_json(path: &Path) -> Option<Value> {
+    fs::read_to_string(path).ok().and_then(|data| serde_json::from_str(&data).ok())
+}
+
+// What the project's manifests declare, read once and shared by the checks below
+struct Manifests {
+    composer: Option<Value>,
+    lock: Option<Value>,
+    package: Option<Value>,
+}
+
+impl Manifests {
+    fn load(p: &Path) -> Self {
+        Self {
+            composer: read_json(&p.join("composer.json")),
+            lock: read_json(&p.join("composer.lock")),
+            package: read_json(&p.join("package.json")),
+        }
+    }
+
+    fn requires(&self, name: &str) -> bool {
+        let declared = self.composer.as_ref().is_some_and(|c| {
+            ["require", "require-dev"].iter().any(|key| c.get(key).and_then(|r| r.get(name)).is_some())
+        });
+        declared || self.locked_version(name).is_some()
+    }
+
+    // Installed version from composer.lock, else the constraint from composer.json
+    fn composer_version(&self, name: &str) -> Option<String> {
+        self.locked_version(name).or_else(|| {
+            self.composer.as_ref()?.get("require")?.get(name)?.as_str().map(str::to_string)
+        })
+    }
+
+    fn locked_version(&self, name: &str) -> Option<String> {
+        let lock = self.lock.as_ref()?;
+        ["packages", "packages-dev"].iter()
+            .filter_map(|key| lock.get(key).and_then(Value::as_array))
+            .flatten()
+            .find(|p| p.get("name").and_then(Value::as_str) == Some(name))
+            .and_then(|p| p.get("version").and_then(Value::as_str))
+            .map(|v| v.trim_start_matches('v').to_string())
+    }
+
+    fn depends_on(&self, name: &str) -> bool {
+        self.package.as_ref().is_some_and(|p| {
+            ["dependencies", "devDependencies"].iter().any(|key| p.get(key).and_then(|d| d.get(name)).is_some())
+        })
+    }
+
+    // Version from node_modules when installed, else the declared range
+    fn package_version(&self, root: &Path, name: &str) -> Option<String> {
+        if let Some(v) = read_json(&root.join("node_modules").join(name).join("package.json"))
+            .and_then(|p| p.get("version").and_then(Value::as_str).map(str::to_string)) {
+            return Some(v);
+        }
+        let package = self.package.as_ref()?;
+        ["dependencies", "devDependencies"].iter()
+            .find_map(|key| package.get(key)?.get(name)?.as_str())
+            .map(str::to_string)
+    }
+
+    fn script(&self, name: &str) -> bool {
+        self.package.as_ref().and_then(|p| p.get("scripts")).and_then(|s| s.get(name)).is_some()
+    }
+}
+
+fn package_manager(p: &Path, package: &Value) -> String {
+    // "packageManager": "pnpm@9.1.0" wins over lockfiles
+    if let Some(declared) = package.get("packageManager").and_then(Value::as_str) {
+        let name = declared.split('@').next().unwrap_or_default();
+        if ["npm", "pnpm", "yarn", "bun"].contains(&name) {
+            return name.to_string();
+        }
+    }
+    let by_lockfile = [
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
+    ];
+    by_lockfile.iter()
+        .find(|(file, _)| p.join(file).exists())
+        .map(|(_, pm)| pm.to_string())
+        .unwrap_or_else(|| "npm".to_string())
+}
+
+fn wordpress_version(root: &Path) -> Option<String> {
+    let source = fs::read_to_string(root.join("wp-includes").join("version.php")).ok()?;
+    let line = source.lines().find(|l| l.trim_start().starts_with("$wp_version"))?;
+    line.split('\'').nth(1).map(str::to_string)
+}
+
+fn php_server(docroot: &str) -> String {
+    format!("php -S 127.0.0.1:{{port}} -t {}", docroot)
+}
+
+// Framework, its version and docroot, checked from the most to the least specific
+fn detect_php(p: &Path, m: &Manifests) -> Option<(&'static str, Option<String>, String)> {
+    if m.requires("statamic/cms") {
+        return Some(("statamic", m.composer_version("statamic/cms"), "public".into()));
+    }
+    if p.join("artisan").exists() && (m.composer.is_none() || m.requires("laravel/framework")) {
+        return Some(("laravel", m.composer_version("laravel/framework"), "public".into()));
+    }
+    // A bare bin/console is used by plenty of non-Symfony apps; the kernel bundle or bundles.php is not
+    if p.join("bin").join("console").exists()
+        && (m.requires("symfony/framework-bundle") || p.join("config").join("bundles.php").exists()) {
+        return Some(("symfony", m.composer_version("symfony/framework-bundle"), "public".into()));
+    }
+    for (package, docroot) in [("roots/wordpress", "web"), ("johnpbloch/wordpress-core", "wordpress")] {
+        if m.requires(package) {
+            return Some(("wordpress", m.composer_version(package).or_else(|| wordpress_version(&p.join(docroot))), docroot.into()));
+        }
+    }
+    if p.join("wp-config.php").exists() || p.join("wp-settings.php").exists() {
+        return Some(("wordpress", wordpress_version(p), ".".into()));
+    }
+    if m.requires("drupal/core") || m.requires("drupal/core-recommended") || p.join("web").join("core").join("lib").join("Drupal.php").exists() {
+        let version = m.composer_version("drupal/core").or_else(|| m.composer_version("drupal/core-recommended"));
+        let docroot = if p.join("web").is_dir() { "web" } else { "." };
+        return Some(("drupal", version, docroot.into()));
+    }
+    if p.join("core").join("lib").join("Drupal.php").exists() {
+        return Some(("drupal", None, ".".into()));
+    }
+    if m.requires("codeigniter4/framework") || (p.join("spark").exists() && p.join("app").join("Config").is_dir()) {
+        return Some(("codeigniter", m.composer_version("codeigniter4/framework"), "public".into()));
+    }
+    if m.requires("cakephp/cakephp") {
+        return Some(("cakephp", m.composer_version("cakephp/cakephp"), "webroot".into()));
+    }
+    None
+}
+
+fn detect_node(p: &Path, m: &Manifests) -> Option<(&'static str, Option<String>)> {
+    let has_config = |stem: &str| ["js", "mjs", "cjs", "ts"].iter().any(|ext| p.join(format!("{}.{}", stem, ext)).exists());
+
+    if m.depends_on("next") || has_config("next.config") {
+        return Some(("next", m.package_version(p, "next")));
+    }
+    if m.depends_on("nuxt") || has_config("nuxt.config") {
+        return Some(("nuxt", m.package_version(p, "nuxt")));
+    }
+    if m.depends_on("astro") || has_config("astro.config") {
+        return Some(("astro", m.package_version(p, "astro")));
+    }
+    if m.depends_on("@sveltejs/kit") || m.depends_on("svelte") || has_config("svelte.config") {
+        let version = m.package_version(p, "@sveltejs/kit").or_else(|| m.package_version(p, "svelte"));
+        return Some(("svelte", version));
+    }
+    // Vite alone says nothing about the UI library
+    if m.depends_on("react") && (m.depends_on("vite") || has_config("vite.config")) {
+        return Some(("react", m.package_version(p, "react")));
+    }
+    if m.depends_on("vite") || has_config("vite.config") {
+        return Some(("vite", m.package_version(p, "vite")));
+    }
+    m.package.as_ref().map(|_| ("node", None))
+}
+
+pub fn detect(p: &Path) -> FrameworkInfo {
+    let m = Manifests::load(p);
+    let php = m.composer.as_ref().and_then(|c| c.get("require")?.get("php")?.as_str().map(str::to_string));
+    let node = m.package.as_ref().and_then(|pkg| pkg.get("engines")?.get("node")?.as_str().map(str::to_string));
+    let package_manager = m.package.as_ref().map(|pkg| package_manager(p, pkg));
+
+    let (framework, version, start_command, docroot) = if let Some((framework, version, docroot)) = detect_php(p, &m) {
+        let start = match framework {
+            "laravel" | "statamic" => "php artisan serve --host=127.0.0.1 --port={port}".to_string(),
+            "codeigniter" => "php spark serve --host 127.0.0.1 --port {port}".to_string(),
+            "cakephp" => "php bin/cake.php server -H 127.0.0.1 -p {port}".to_string(),
+            _ => php_server(&docroot),
+        };
+        (framework, version, Some(start), Some(docroot))
+    } else if let Some((framework, version)) = detect_node(p, &m) {
+        let pm = package_manager.clone().unwrap_or_else(|| "npm".to_string());
+        let script = ["dev", "start", "serve"].into_iter().find(|s| m.script(s));
+        (framework, version, script.map(|s| format!("{} run {}", pm, s)), None)
+    } else {
+        ("custom", None, Some(php_server(".")), Some(".".to_string()))
+    };
+
+    FrameworkInfo {
+        framework: framework.to_string(),
+        version,
+        php,
+        node,
+        tasks: default_tasks(framework, m.package.is_some()),
+        package_manager,
+        start_command,
+        docroot,
+    }
+}
+
+#[tauri::command]
+pub fn detect_framework(path: String) -> FrameworkInfo {
+    detect(Path::new(&path))
+}
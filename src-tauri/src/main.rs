@@ -13,23 +13,66 @@ mod composer;
 mod terminal;
 mod hosts; 
 mod proxy;
+mod cache;
+mod network;
+mod versions;
+mod php_extensions;
+mod php_ini;
+mod project_ini;
+mod xdebug;
+mod db_instances;
+mod db_client;
+mod env_file;
+mod db_provision;
+mod db_backup;
+mod credentials;
+mod db_upgrade;
+mod dev_services;
+mod db_query;
+mod db_transfer;
+mod scaffold;
+mod pipelines;
+mod wordpress;
+mod tasks;
+mod frameworks;
+mod runtimes;
 
 use std::sync::Arc;
 use tauri::Manager; 
 use std::fs::OpenOptions;
 use std::path::PathBuf;
 
-use process_manager::{start_service, stop_service, ServiceState};
+use process_manager::{start_service, stop_service, restart_service, ServiceState};
 use filesystem::{
     init_environment, get_services, get_service_bin_path, get_user_home, 
     delete_service_folder, delete_project_dir, check_projects_status, 
-    detect_framework, prepare_php_ini, get_node_versions, get_node_path, patch_vite_config, open_file_in_editor
+    prepare_php_ini, get_node_versions, get_node_path, patch_vite_config, open_file_in_editor
 };
-use downloader::{download_service, install_adminer_file, download_postgresql, download_php_robust};
+use downloader::{download_service, install_adminer_file, download_postgresql, download_php_robust, install_service_from_file, download_node};
+use cache::{list_cache, prune_cache};
+use network::{get_network_settings, save_network_settings};
+use versions::{uninstall_service, upgrade_service};
+use php_extensions::{list_php_extensions, enable_php_extension, disable_php_extension, install_php_extension, get_php_build_info};
+use php_ini::{get_php_ini_settings, set_php_ini_setting, unset_php_ini_setting};
+use project_ini::{get_project_php_overrides, set_project_php_override, unset_project_php_override, get_effective_php_config};
+use xdebug::{enable_debug_mode, disable_debug_mode, get_debug_status};
+use db_instances::{list_db_instances, create_db_instance, start_db_instance, stop_db_instance, delete_db_instance};
+use db_provision::{create_project_database, list_project_databases, drop_project_database};
+use db_backup::{backup_database, restore_database, list_backups, delete_backup, get_backup_settings, save_backup_settings, start_backup_scheduler};
+use db_upgrade::upgrade_db_instance;
+use db_query::{run_sql_query, list_instance_databases, list_tables, describe_table};
+use db_transfer::{import_sql_file, export_database};
+use scaffold::{list_project_templates, save_project_template, delete_project_template, create_project};
+use pipelines::{get_post_create_pipeline, save_post_create_pipeline, run_post_create};
+use wordpress::run_wp_cli;
+use frameworks::detect_framework;
+use runtimes::resolve_project_runtimes;
+use tasks::{list_project_tasks, suggest_project_tasks, save_project_task, delete_project_task, run_project_task, stop_project_task};
+use dev_services::{install_dev_service, start_dev_service, stop_dev_service, get_dev_services_status};
 use shim::{set_active_version, get_active_version};
 use store::{save_projects, load_projects};
-use database::{init_mysql, change_mariadb_password, init_postgresql, change_postgres_password};
-use composer::{init_composer, create_laravel_project, create_wordpress_project, create_symfony_project};
+use database::{init_mysql, change_mariadb_password, init_postgresql, change_postgres_password, migrate_postgres_auth, set_db_instance_port, get_db_instance_password};
+use composer::{init_composer, run_composer, composer_self_update};
 use terminal::open_project_terminal;
 use hosts::{add_host_entry, remove_host_entry};
 use proxy::{start_proxy_server, register_proxy_route, ProxyState};
@@ -64,6 +107,10 @@ fn main() {
         start_proxy_server(proxy_state_clone).await;
     });
 
+    tauri::async_runtime::spawn(async move {
+        start_backup_scheduler().await;
+    });
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .manage(service_state)
@@ -86,8 +133,6 @@ fn main() {
             init_mysql,
             change_mariadb_password,
             init_composer,
-            create_laravel_project,
-            create_wordpress_project,
             open_project_terminal,
             delete_project_dir,
             check_projects_status,
@@ -101,11 +146,79 @@ fn main() {
             get_node_path,
             patch_vite_config,
             open_file_in_editor,
-            create_symfony_project,
             download_postgresql,
             download_php_robust,
             init_postgresql,
             change_postgres_password,
+            install_service_from_file,
+            list_cache,
+            prune_cache,
+            get_network_settings,
+            save_network_settings,
+            download_node,
+            uninstall_service,
+            upgrade_service,
+            list_php_extensions,
+            enable_php_extension,
+            disable_php_extension,
+            install_php_extension,
+            get_php_build_info,
+            get_php_ini_settings,
+            set_php_ini_setting,
+            unset_php_ini_setting,
+            get_project_php_overrides,
+            set_project_php_override,
+            unset_project_php_override,
+            get_effective_php_config,
+            restart_service,
+            enable_debug_mode,
+            disable_debug_mode,
+            get_debug_status,
+            list_db_instances,
+            create_db_instance,
+            start_db_instance,
+            stop_db_instance,
+            delete_db_instance,
+            create_project_database,
+            list_project_databases,
+            drop_project_database,
+            backup_database,
+            restore_database,
+            list_backups,
+            delete_backup,
+            get_backup_settings,
+            save_backup_settings,
+            migrate_postgres_auth,
+            set_db_instance_port,
+            get_db_instance_password,
+            upgrade_db_instance,
+            install_dev_service,
+            start_dev_service,
+            stop_dev_service,
+            get_dev_services_status,
+            run_sql_query,
+            list_instance_databases,
+            list_tables,
+            describe_table,
+            import_sql_file,
+            export_database,
+            list_project_templates,
+            save_project_template,
+            delete_project_template,
+            create_project,
+            get_post_create_pipeline,
+            save_post_create_pipeline,
+            run_post_create,
+            run_wp_cli,
+            run_composer,
+            composer_self_update,
+            list_project_tasks,
+            suggest_project_tasks,
+            save_project_task,
+            delete_project_task,
+            run_project_task,
+            stop_project_task,
+            resolve_project_runtimes,
         ])
         .build(tauri::generate_context!())
         .expect("error building tauri app")
@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::PathBuf;
+use std::env;
+use reqwest::{Certificate, Client, NoProxy, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use crate::filesystem::get_home;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MirrorRule {
+    // Host to redirect, e.g. "windows.php.net"
+    pub host: String,
+    // Base URL the original path is appended to, e.g. "https://artifactory.corp/php"
+    pub target: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct NetworkSettings {
+    // Explicit proxy; when empty HTTP_PROXY / HTTPS_PROXY / NO_PROXY from the environment apply.
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    // PEM bundle added to the trusted roots (falls back to SSL_CERT_FILE).
+    pub ca_bundle: Option<String>,
+    pub mirrors: Vec<MirrorRule>,
+}
+
+fn get_settings_path() -> Option<PathBuf> {
+    let home = get_home()?;
+    Some(home.join(".stackmanager").join("network.json"))
+}
+
+pub fn load_settings() -> NetworkSettings {
+    get_settings_path()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn build_client_with(settings: &NetworkSettings) -> Result<Client, String> {
+    let mut builder = Client::builder().user_agent("StackManager/1.0");
+
+    if let Some(proxy_url) = non_empty(&settings.proxy) {
+        let mut proxy = Proxy::all(proxy_url).map_err(|e| format!("Invalid proxy: {}", e))?;
+        if let Some(list) = non_empty(&settings.no_proxy) {
+            proxy = proxy.no_proxy(NoProxy::from_string(list));
+        }
+        builder = builder.proxy(proxy);
+    }
+
+    let ca_bundle = non_empty(&settings.ca_bundle)
+        .map(str::to_string)
+        .or_else(|| env::var("SSL_CERT_FILE").ok().filter(|v| !v.is_empty()));
+
+    if let Some(path) = ca_bundle {
+        let pem = fs::read(&path).map_err(|e| format!("Failed to read CA bundle {}: {}", path, e))?;
+        let certs = Certificate::from_pem_bundle(&pem).map_err(|e| format!("Invalid CA bundle {}: {}", path, e))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+// Shared client for every outbound download so proxy and CA settings apply everywhere.
+pub fn build_client() -> Result<Client, String> {
+    build_client_with(&load_settings())
+}
+
+// Applies the configured mirror rules; returns the URL unchanged when no rule matches.
+pub fn rewrite_url(url: &str) -> String {
+    let settings = load_settings();
+    let parsed = match Url::parse(url) {
+        Ok(u) => u,
+        Err(_) => return url.to_string(),
+    };
+    let host = parsed.host_str().unwrap_or_default();
+
+    for rule in &settings.mirrors {
+        if rule.host.eq_ignore_ascii_case(host) {
+            let mut rewritten = format!("{}{}", rule.target.trim_end_matches('/'), parsed.path());
+            if let Some(query) = parsed.query() {
+                rewritten.push('?');
+                rewritten.push_str(query);
+            }
+            println!("Mirror: {} -> {}", url, rewritten);
+            return rewritten;
+        }
+    }
+
+    url.to_string()
+}
+
+#[tauri::command]
+pub fn get_network_settings() -> NetworkSettings {
+    load_settings()
+}
+
+#[tauri::command]
+pub fn save_network_settings(settings: NetworkSettings) -> Result<String, String> {
+    for rule in &settings.mirrors {
+        if rule.host.trim().is_empty() { return Err("Mirror host cannot be empty".to_string()); }
+        Url::parse(&rule.target).map_err(|e| format!("Invalid mirror target {}: {}", rule.target, e))?;
+    }
+
+    // Fails early on a malformed proxy URL or unreadable CA bundle
+    build_client_with(&settings)?;
+
+    let path = get_settings_path().ok_or("Could not find home directory")?;
+    let data = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| e.to_string())?;
+    Ok("Network settings saved".to_string())
+}
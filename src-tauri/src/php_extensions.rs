@@ -0,0 +1,303 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::downloader::{extract_archive, fetch_cached};
+use crate::network;
+use crate::php_ini::{IniEntry, IniFile};
+
+// Extensions that must be loaded with zend_extension= instead of extension=
+const ZEND_EXTENSIONS: [&str; 3] = ["xdebug", "opcache", "ioncube_loader"];
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PhpExtension {
+    pub name: String,
+    // A loadable file exists in ext/
+    pub available: bool,
+    // Uncommented extension= / zend_extension= line in php.ini
+    pub enabled: bool,
+    // Reported by `php -m`
+    pub loaded: bool,
+    pub zend: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PhpBuild {
+    pub version: String,
+    pub thread_safe: bool,
+    pub arch: String,
+    pub compiler: String,
+}
+
+fn php_exe(dir: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") { dir.join("php.exe") } else { dir.join("php") }
+}
+
+fn run_php(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new(php_exe(dir));
+    command.args(args).current_dir(dir);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output().map_err(|e| format!("Failed to run php: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// "php_redis.dll" / "redis.so" -> "redis"
+fn extension_name_from_file(file_name: &str) -> Option<String> {
+    let lower = file_name.to_lowercase();
+    let stem = lower.strip_suffix(".dll").or_else(|| lower.strip_suffix(".so"))?;
+    Some(stem.strip_prefix("php_").unwrap_or(stem).to_string())
+}
+
+// Normalizes the value of an extension= directive, which may be a bare name, a file name or a path
+pub fn extension_name_from_value(value: &str) -> String {
+    let value = value.trim().trim_matches('"');
+    let file = value.rsplit(['/', '\\']).next().unwrap_or(value);
+    extension_name_from_file(file).unwrap_or_else(|| file.to_lowercase())
+}
+
+fn is_zend(name: &str) -> bool {
+    ZEND_EXTENSIONS.contains(&name)
+}
+
+fn ini_path(dir: &Path) -> Result<PathBuf, String> {
+    let path = dir.join("php.ini");
+    if !path.exists() {
+        return Err("php.ini not found. Run prepare_php_ini first.".to_string());
+    }
+    Ok(path)
+}
+
+// extension= and zend_extension= lines that load `name`
+fn extension_entries(ini: &IniFile, name: &str) -> Vec<IniEntry> {
+    ini.entries()
+        .into_iter()
+        .filter(|e| (e.key == "extension" || e.key == "zend_extension") && extension_name_from_value(&e.value) == name)
+        .collect()
+}
+
+pub fn is_extension_enabled(ini: &IniFile, name: &str) -> bool {
+    extension_entries(ini, &extension_name_from_value(name)).iter().any(|e| !e.commented)
+}
+
+pub fn extension_file(dir: &Path, name: &str) -> PathBuf {
+    let file_name = if cfg!(target_os = "windows") { format!("php_{}.dll", name) } else { format!("{}.so", name) };
+    dir.join("ext").join(file_name)
+}
+
+// Toggles an extension in place. When enabling, a commented template line is preferred;
+// a new line is only appended if `append_if_missing` is set.
+pub fn set_extension_enabled_in(ini: &mut IniFile, name: &str, enable: bool, append_if_missing: bool) {
+    let name = extension_name_from_value(name);
+    let entries = extension_entries(ini, &name);
+
+    if enable {
+        if entries.iter().any(|e| !e.commented) { return; }
+        match entries.first() {
+            Some(entry) => ini.uncomment_line(entry.index),
+            None if append_if_missing => {
+                let key = if is_zend(&name) { "zend_extension" } else { "extension" };
+                ini.push(key, &name);
+            }
+            None => {}
+        }
+    } else {
+        for entry in entries.iter().filter(|e| !e.commented) {
+            ini.comment_line(entry.index);
+        }
+    }
+}
+
+pub fn set_extension_enabled(dir: &Path, name: &str, enable: bool) -> Result<bool, String> {
+    let path = ini_path(dir)?;
+    let mut ini = IniFile::load(&path)?;
+    set_extension_enabled_in(&mut ini, name, enable, true);
+
+    if ini.is_modified() {
+        ini.save(&path)?;
+    }
+    Ok(ini.is_modified())
+}
+
+#[tauri::command]
+pub fn list_php_extensions(bin_path_dir: String) -> Result<Vec<PhpExtension>, String> {
+    let dir = PathBuf::from(&bin_path_dir);
+    let mut extensions: BTreeMap<String, PhpExtension> = BTreeMap::new();
+
+    if let Ok(entries) = fs::read_dir(dir.join("ext")) {
+        for entry in entries.flatten() {
+            if let Some(name) = extension_name_from_file(&entry.file_name().to_string_lossy()) {
+                let ext = extensions.entry(name.clone()).or_default();
+                ext.name = name;
+                ext.available = true;
+            }
+        }
+    }
+
+    if let Ok(ini) = ini_path(&dir).and_then(|p| IniFile::load(&p)) {
+        for entry in ini.entries() {
+            if entry.key != "extension" && entry.key != "zend_extension" { continue; }
+            let name = extension_name_from_value(&entry.value);
+            let ext = extensions.entry(name.clone()).or_default();
+            ext.name = name;
+            ext.zend |= entry.key == "zend_extension";
+            ext.enabled |= !entry.commented;
+        }
+    }
+
+    // Compiled-in modules (Core, date, ...) show up here without an ext/ file
+    if let Ok(modules) = run_php(&dir, &["-m"]) {
+        for line in modules.lines() {
+            let module = line.trim();
+            if module.is_empty() || module.starts_with('[') { continue; }
+            let name = module.to_lowercase().replace(' ', "_");
+            let name = if name == "zend_opcache" { "opcache".to_string() } else { name };
+            let ext = extensions.entry(name.clone()).or_default();
+            ext.name = name;
+            ext.loaded = true;
+        }
+    }
+
+    for ext in extensions.values_mut() {
+        ext.zend |= is_zend(&ext.name);
+    }
+
+    Ok(extensions.into_values().collect())
+}
+
+#[tauri::command]
+pub fn enable_php_extension(bin_path_dir: String, name: String) -> Result<String, String> {
+    let dir = PathBuf::from(&bin_path_dir);
+    let file = extension_file(&dir, &name);
+    if !file.exists() && !is_zend(&name) {
+        return Err(format!("{:?} is not installed. Install it first.", file));
+    }
+
+    if set_extension_enabled(&dir, &name, true)? {
+        Ok(format!("Enabled {}", name))
+    } else {
+        Ok(format!("{} already enabled", name))
+    }
+}
+
+#[tauri::command]
+pub fn disable_php_extension(bin_path_dir: String, name: String) -> Result<String, String> {
+    let dir = PathBuf::from(&bin_path_dir);
+    if set_extension_enabled(&dir, &name, false)? {
+        Ok(format!("Disabled {}", name))
+    } else {
+        Ok(format!("{} was not enabled", name))
+    }
+}
+
+// Visual C++ release year -> windows.php.net build tag
+fn compiler_tag(compiler: &str) -> Option<&'static str> {
+    if compiler.contains("2022") { Some("vs17") }
+    else if compiler.contains("2019") { Some("vs16") }
+    else if compiler.contains("2017") { Some("vc15") }
+    else if compiler.contains("2015") { Some("vc14") }
+    else { None }
+}
+
+pub fn detect_php_build(dir: &Path) -> Result<PhpBuild, String> {
+    let info = run_php(dir, &["-i"])?;
+    let field = |key: &str| -> Option<String> {
+        info.lines()
+            .find(|l| l.starts_with(key))
+            .and_then(|l| l.split_once("=>"))
+            .map(|(_, v)| v.trim().to_string())
+    };
+
+    let version = field("PHP Version").ok_or("Could not determine PHP version")?;
+    let thread_safe = field("Thread Safety").map(|v| v == "enabled").unwrap_or(true);
+    let arch = field("Architecture").unwrap_or_else(|| "x64".to_string());
+    let compiler = field("Compiler")
+        .as_deref()
+        .and_then(compiler_tag)
+        .unwrap_or("vs16")
+        .to_string();
+
+    Ok(PhpBuild { version, thread_safe, arch, compiler })
+}
+
+#[tauri::command]
+pub fn get_php_build_info(bin_path_dir: String) -> Result<PhpBuild, String> {
+    detect_php_build(&PathBuf::from(&bin_path_dir))
+}
+
+async fn latest_pecl_version(name: &str) -> Result<String, String> {
+    let client = network::build_client()?;
+    let url = network::rewrite_url(&format!("https://pecl.php.net/rest/r/{}/stable.txt", name));
+    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() { return Err(format!("Unknown PECL package {}: {}", name, res.status())); }
+    Ok(res.text().await.map_err(|e| e.to_string())?.trim().to_string())
+}
+
+// Downloads a prebuilt DLL matching the PHP build's version, thread safety, compiler and arch, then enables it
+pub async fn install_extension_into(dir: &Path, name: &str, version: Option<String>) -> Result<String, String> {
+    if !cfg!(target_os = "windows") {
+        return Err(format!("Prebuilt PECL binaries are only available for Windows. Install {} with `pecl install {}`.", name, name));
+    }
+
+    let build = detect_php_build(dir)?;
+    let php_minor = build.version.split('.').take(2).collect::<Vec<_>>().join(".");
+    let ext_version = match version {
+        Some(v) if !v.trim().is_empty() => v,
+        _ => latest_pecl_version(name).await?,
+    };
+    let ts = if build.thread_safe { "ts" } else { "nts" };
+
+    let ext_dir = dir.join("ext");
+    if !ext_dir.exists() { fs::create_dir_all(&ext_dir).map_err(|e| e.to_string())?; }
+    let client = network::build_client()?;
+
+    // Xdebug stopped publishing to PECL's Windows mirror; xdebug.org ships bare DLLs instead
+    if name == "xdebug" {
+        let arch = if build.arch == "x64" { "x86_64" } else { build.arch.as_str() };
+        let nts = if build.thread_safe { "" } else { "-nts" };
+        let url = format!("https://xdebug.org/files/php_xdebug-{}-{}-{}{}-{}.dll", ext_version, php_minor, build.compiler, nts, arch);
+        let dll = fetch_cached(&client, &url).await
+            .map_err(|e| format!("No xdebug {} build for PHP {}: {}", ext_version, php_minor, e))?;
+        fs::copy(&dll, ext_dir.join("php_xdebug.dll")).map_err(|e| e.to_string())?;
+    } else {
+        let url = format!(
+            "https://windows.php.net/downloads/pecl/releases/{name}/{ver}/php_{name}-{ver}-{php}-{ts}-{cc}-{arch}.zip",
+            name = name, ver = ext_version, php = php_minor, ts = ts, cc = build.compiler, arch = build.arch
+        );
+        let archive = fetch_cached(&client, &url).await
+            .map_err(|e| format!("No {} {} build for PHP {} {} {} {}: {}", name, ext_version, php_minor, ts, build.compiler, build.arch, e))?;
+
+        let staging = dir.join(format!(".pecl-{}", name));
+        if staging.exists() { fs::remove_dir_all(&staging).ok(); }
+        extract_archive(&archive, &staging)?;
+
+        // php_<ext>.dll goes to ext/, bundled runtime DLLs (e.g. ImageMagick's CORE_RL_*) next to php.exe
+        let result = (|| -> Result<(), String> {
+            for entry in fs::read_dir(&staging).map_err(|e| e.to_string())?.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let lower = file_name.to_lowercase();
+                if !lower.ends_with(".dll") { continue; }
+                let target = if lower.starts_with("php_") { ext_dir.join(&file_name) } else { dir.join(&file_name) };
+                fs::copy(entry.path(), target).map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        })();
+        fs::remove_dir_all(&staging).ok();
+        result?;
+    }
+
+    set_extension_enabled(dir, name, true)?;
+    Ok(format!("Installed {} {} for PHP {}", name, ext_version, build.version))
+}
+
+#[tauri::command]
+pub async fn install_php_extension(bin_path_dir: String, name: String, version: Option<String>) -> Result<String, String> {
+    install_extension_into(&PathBuf::from(&bin_path_dir), &name.to_lowercase(), version).await
+}
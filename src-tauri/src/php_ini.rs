@@ -0,0 +1,476 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IniDirective {
+    pub section: String,
+    pub key: String,
+    pub value: String,
+}
+
+// A directive found on one physical line, active or commented out
+#[derive(Clone, Debug)]
+pub struct IniEntry {
+    pub index: usize,
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub commented: bool,
+}
+
+// php.ini as a list of lines. Edits replace single lines in place, so comments,
+// sections, ordering and the trailing newline survive and diffs stay minimal.
+#[derive(Clone, Debug, Default)]
+pub struct IniFile {
+    lines: Vec<String>,
+    trailing_newline: bool,
+    modified: bool,
+}
+
+fn is_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '[' | ']'))
+}
+
+fn strip_quotes(value: &str) -> &str {
+    let v = value.trim();
+    if v.len() >= 2 && ((v.starts_with('"') && v.ends_with('"')) || (v.starts_with('\'') && v.ends_with('\''))) {
+        &v[1..v.len() - 1]
+    } else {
+        v
+    }
+}
+
+// Drops a trailing "; comment" outside of quotes
+fn strip_inline_comment(value: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => return value[..i].trim_end(),
+            _ => {}
+        }
+    }
+    value.trim_end()
+}
+
+fn parse_directive(line: &str) -> Option<(bool, String, String)> {
+    let trimmed = line.trim();
+    let (commented, rest) = match trimmed.strip_prefix(';') {
+        // Template lines follow ';' directly; "; " and ";   extension=mysqli" are documentation
+        Some(r) if r.starts_with(char::is_whitespace) => return None,
+        Some(r) => (true, r),
+        None => (false, trimmed),
+    };
+    let (key, value) = rest.split_once('=')?;
+    let key = key.trim();
+    if !is_key(key) { return None; }
+
+    let value = strip_inline_comment(value.trim());
+    // Prose in comments ("; extension=modulename loads ...") is not a directive
+    if commented && value.contains(' ') && !value.starts_with('"') { return None; }
+
+    Some((commented, key.to_string(), strip_quotes(value).to_string()))
+}
+
+fn format_value(value: &str) -> String {
+    let needs_quotes = value.contains(|c: char| c.is_whitespace() || matches!(c, ';' | '=' | '&' | '|' | '!' | '~' | '{' | '}'));
+    if needs_quotes && !value.starts_with('"') { format!("\"{}\"", value) } else { value.to_string() }
+}
+
+impl IniFile {
+    pub fn parse(content: &str) -> Self {
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+            trailing_newline: content.ends_with('\n'),
+            modified: false,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn entries(&self) -> Vec<IniEntry> {
+        let mut section = String::new();
+        let mut entries = Vec::new();
+
+        for (index, line) in self.lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                section = trimmed[1..trimmed.len() - 1].to_string();
+                continue;
+            }
+            if let Some((commented, key, value)) = parse_directive(line) {
+                entries.push(IniEntry { index, section: section.clone(), key, value, commented });
+            }
+        }
+        entries
+    }
+
+    // Active directives in file order; PHP lets the last occurrence win
+    pub fn directives(&self) -> Vec<IniDirective> {
+        self.entries()
+            .into_iter()
+            .filter(|e| !e.commented)
+            .map(|e| IniDirective { section: e.section, key: e.key, value: e.value })
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries().into_iter().rev().find(|e| !e.commented && e.key == key).map(|e| e.value)
+    }
+
+    fn replace_line(&mut self, index: usize, new_line: String) {
+        if self.lines[index] != new_line {
+            self.lines[index] = new_line;
+            self.modified = true;
+        }
+    }
+
+    // Rewrites "key = old ; note" as "key = new ; note", keeping the original spacing around '='
+    fn render(&self, index: usize, key: &str, value: &str) -> String {
+        let raw = self.lines[index].trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+        let (spaced, comment) = match raw.split_once('=') {
+            Some((k, v)) => {
+                let v = v.trim();
+                (k.ends_with(' '), v[strip_inline_comment(v).len()..].trim().to_string())
+            }
+            None => (true, String::new()),
+        };
+
+        let mut line = if spaced { format!("{} = {}", key, format_value(value)) } else { format!("{}={}", key, format_value(value)) };
+        if !comment.is_empty() {
+            line.push(' ');
+            line.push_str(&comment);
+        }
+        line
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        let entries = self.entries();
+
+        // Last active occurrence, otherwise the last commented-out template line, otherwise append
+        let target = entries.iter().rev().find(|e| !e.commented && e.key == key)
+            .or_else(|| entries.iter().rev().find(|e| e.commented && e.key == key))
+            .map(|e| e.index);
+
+        match target {
+            Some(index) => {
+                let line = self.render(index, key, value);
+                self.replace_line(index, line);
+            }
+            None => self.push(key, value),
+        }
+    }
+
+    // Comments out every active occurrence rather than deleting, so the template line stays documented
+    pub fn unset(&mut self, key: &str) -> bool {
+        let targets: Vec<usize> = self.entries().into_iter()
+            .filter(|e| !e.commented && e.key == key)
+            .map(|e| e.index)
+            .collect();
+        for index in &targets {
+            self.comment_line(*index);
+        }
+        !targets.is_empty()
+    }
+
+    pub fn comment_line(&mut self, index: usize) {
+        let line = format!(";{}", self.lines[index].trim_start());
+        self.replace_line(index, line);
+    }
+
+    pub fn uncomment_line(&mut self, index: usize) {
+        let line = self.lines[index].trim_start().trim_start_matches(';').trim_start().to_string();
+        self.replace_line(index, line);
+    }
+
+    // Appends to the end of the [PHP] section (before the next section header) or the file
+    pub fn push(&mut self, key: &str, value: &str) {
+        let line = format!("{} = {}", key, format_value(value));
+        let mut in_php = false;
+        let mut insert_at = None;
+
+        for (index, raw) in self.lines.iter().enumerate() {
+            let trimmed = raw.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                if in_php { insert_at = Some(index); break; }
+                in_php = trimmed.eq_ignore_ascii_case("[php]");
+            }
+        }
+
+        match insert_at {
+            Some(index) => {
+                // Keep the blank line that usually separates sections
+                let index = if index > 0 && self.lines[index - 1].trim().is_empty() { index - 1 } else { index };
+                self.lines.insert(index, line);
+            }
+            None => self.lines.push(line),
+        }
+        self.modified = true;
+    }
+}
+
+impl std::fmt::Display for IniFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.lines.join("\n"))?;
+        if self.trailing_newline { writeln!(f)?; }
+        Ok(())
+    }
+}
+
+enum DirectiveType { Size, Bool, Int, Path }
+
+fn directive_type(key: &str) -> Option<DirectiveType> {
+    match key {
+        "memory_limit" | "post_max_size" | "upload_max_filesize" | "realpath_cache_size" => Some(DirectiveType::Size),
+        "display_errors" | "display_startup_errors" | "log_errors" | "html_errors" | "short_open_tag"
+        | "file_uploads" | "allow_url_fopen" | "allow_url_include" | "expose_php"
+        | "opcache.enable" | "opcache.enable_cli" | "opcache.validate_timestamps" | "session.use_strict_mode" => Some(DirectiveType::Bool),
+        "max_execution_time" | "max_input_time" | "max_input_vars" | "max_file_uploads" | "precision"
+        | "opcache.memory_consumption" | "opcache.max_accelerated_files" | "opcache.revalidate_freq"
+        | "xdebug.client_port" | "zend.assertions" => Some(DirectiveType::Int),
+        "extension_dir" | "error_log" | "upload_tmp_dir" | "sys_temp_dir" | "session.save_path"
+        | "curl.cainfo" | "openssl.cafile" | "xdebug.output_dir" | "xdebug.log" => Some(DirectiveType::Path),
+        _ => None,
+    }
+}
+
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if !is_key(key) { return Err(format!("Invalid directive name: {}", key)); }
+    if value.contains(['\n', '\r']) { return Err("Values cannot span multiple lines".to_string()); }
+
+    match directive_type(key) {
+        Some(DirectiveType::Size) => {
+            let number = value.strip_suffix(['K', 'M', 'G', 'k', 'm', 'g']).unwrap_or(value);
+            let ok = value == "-1" || number.parse::<u64>().is_ok();
+            if !ok { return Err(format!("{} expects a size like 128M, 1G or -1 (got '{}')", key, value)); }
+        }
+        Some(DirectiveType::Bool) => {
+            let ok = matches!(value.to_lowercase().as_str(), "on" | "off" | "1" | "0" | "true" | "false" | "yes" | "no" | "")
+                || (key == "display_errors" && matches!(value.to_lowercase().as_str(), "stderr" | "stdout"));
+            if !ok { return Err(format!("{} expects On or Off (got '{}')", key, value)); }
+        }
+        Some(DirectiveType::Int) => {
+            value.parse::<i64>().map_err(|_| format!("{} expects a whole number (got '{}')", key, value))?;
+        }
+        Some(DirectiveType::Path) => {
+            if value.contains('"') { return Err(format!("{} cannot contain quotes", key)); }
+            let path = Path::new(value);
+            let must_be_dir = matches!(key, "upload_tmp_dir" | "sys_temp_dir" | "xdebug.output_dir");
+            if must_be_dir && path.is_absolute() && !path.is_dir() {
+                return Err(format!("{} points to a missing directory: {}", key, value));
+            }
+            if let Some(parent) = path.parent() {
+                if path.is_absolute() && !parent.as_os_str().is_empty() && !parent.exists() {
+                    return Err(format!("{}: parent directory does not exist: {:?}", key, parent));
+                }
+            }
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+fn ini_path(bin_path_dir: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(bin_path_dir).join("php.ini");
+    if !path.exists() { return Err("php.ini not found. Run prepare_php_ini first.".to_string()); }
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn get_php_ini_settings(bin_path_dir: String) -> Result<Vec<IniDirective>, String> {
+    let ini = IniFile::load(&ini_path(&bin_path_dir)?)?;
+    Ok(ini.directives())
+}
+
+#[tauri::command]
+pub fn set_php_ini_setting(bin_path_dir: String, key: String, value: String) -> Result<String, String> {
+    let key = key.trim();
+    let value = value.trim();
+    validate_setting(key, value)?;
+
+    let path = ini_path(&bin_path_dir)?;
+    let mut ini = IniFile::load(&path)?;
+    ini.set(key, value);
+
+    if ini.is_modified() {
+        ini.save(&path)?;
+        Ok(format!("Set {} = {}", key, value))
+    } else {
+        Ok(format!("{} already set to {}", key, value))
+    }
+}
+
+#[tauri::command]
+pub fn unset_php_ini_setting(bin_path_dir: String, key: String) -> Result<String, String> {
+    let path = ini_path(&bin_path_dir)?;
+    let mut ini = IniFile::load(&path)?;
+
+    if ini.unset(key.trim()) {
+        ini.save(&path)?;
+        Ok(format!("Unset {}", key))
+    } else {
+        Ok(format!("{} was not set", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::php_extensions::{is_extension_enabled, set_extension_enabled_in};
+
+    // Excerpt of php.ini-development as shipped with PHP 8.x
+    const STOCK_INI: &str = r#"[PHP]
+
+;;;;;;;;;;;;;;;;;;;
+; About php.ini   ;
+;;;;;;;;;;;;;;;;;;;
+; Directives are specified using the following syntax:
+; directive = value
+; Directive names are *case sensitive* - foo=bar is different from FOO=bar.
+
+; memory_limit
+;   Default Value: 128M
+memory_limit = 128M
+
+; Directory in which the loadable extensions (modules) reside.
+; https://php.net/extension-dir
+;extension_dir = "./"
+; On windows:
+;extension_dir = "ext"
+
+;;;;;;;;;;;;;;;;;;;;;;
+; Dynamic Extensions ;
+;;;;;;;;;;;;;;;;;;;;;;
+
+; If you wish to have an extension loaded automatically, use the following
+; syntax:
+;
+;   extension=modulename
+;
+; For example:
+;
+;   extension=mysqli
+;
+; When the extension library to load is not located in the default extension
+; directory, You may specify an absolute path to the library file:
+;
+;   extension=/path/to/extension/mysqli.so
+;
+;extension=bz2
+;extension=curl
+;extension=mysqli
+;extension=openssl
+;zend_extension=opcache
+
+[CLI Server]
+; Whether the CLI web server uses ANSI color coding in its terminal output.
+cli_server.color = On
+
+[Date]
+; Defines the default timezone used by the date functions
+; https://php.net/date.timezone
+;date.timezone =
+"#;
+
+    fn line_count(ini: &IniFile) -> usize {
+        ini.to_string().lines().count()
+    }
+
+    #[test]
+    fn round_trips_unchanged() {
+        let ini = IniFile::parse(STOCK_INI);
+        assert_eq!(ini.to_string(), STOCK_INI);
+        assert!(!ini.is_modified());
+    }
+
+    #[test]
+    fn documentation_examples_are_not_entries() {
+        let ini = IniFile::parse(STOCK_INI);
+        let extensions: Vec<String> = ini.entries().into_iter()
+            .filter(|e| e.key == "extension")
+            .map(|e| e.value)
+            .collect();
+        assert_eq!(extensions, ["bz2", "curl", "mysqli", "openssl"]);
+        assert!(ini.entries().iter().all(|e| e.key != "directive" && e.key != "foo"));
+    }
+
+    #[test]
+    fn reads_active_directives_with_sections() {
+        let ini = IniFile::parse(STOCK_INI);
+        assert_eq!(ini.get("memory_limit").as_deref(), Some("128M"));
+        assert_eq!(ini.get("extension_dir"), None);
+        let color = ini.directives().into_iter().find(|d| d.key == "cli_server.color").unwrap();
+        assert_eq!(color.section, "CLI Server");
+        assert_eq!(color.value, "On");
+    }
+
+    #[test]
+    fn set_uses_the_template_line() {
+        let mut ini = IniFile::parse(STOCK_INI);
+        ini.set("date.timezone", "UTC");
+        ini.set("extension_dir", "ext");
+        assert_eq!(line_count(&ini), STOCK_INI.lines().count());
+        assert!(ini.to_string().contains("\ndate.timezone = UTC\n"));
+        // The last commented occurrence (the Windows one) is the one taken over
+        assert!(ini.to_string().contains("\n;extension_dir = \"./\"\n; On windows:\nextension_dir = ext\n"));
+        assert_eq!(ini.get("extension_dir").as_deref(), Some("ext"));
+    }
+
+    #[test]
+    fn set_replaces_an_active_value_in_place() {
+        let mut ini = IniFile::parse(STOCK_INI);
+        ini.set("memory_limit", "512M");
+        assert_eq!(ini.get("memory_limit").as_deref(), Some("512M"));
+        assert_eq!(line_count(&ini), STOCK_INI.lines().count());
+        assert!(ini.is_modified());
+    }
+
+    #[test]
+    fn unset_comments_out_and_push_stays_in_php_section() {
+        let mut ini = IniFile::parse(STOCK_INI);
+        assert!(ini.unset("memory_limit"));
+        assert!(ini.to_string().contains("\n;memory_limit = 128M\n"));
+        assert!(!ini.unset("memory_limit"));
+
+        ini.push("max_input_vars", "5000");
+        let text = ini.to_string();
+        let pushed = text.find("max_input_vars = 5000").unwrap();
+        assert!(pushed < text.find("[CLI Server]").unwrap());
+    }
+
+    #[test]
+    fn enabling_an_extension_uncomments_the_real_line() {
+        let mut ini = IniFile::parse(STOCK_INI);
+        set_extension_enabled_in(&mut ini, "mysqli", true, false);
+        let text = ini.to_string();
+        assert!(text.contains("\nextension=mysqli\n"));
+        assert!(text.contains("\n;   extension=mysqli\n"));
+        assert!(is_extension_enabled(&ini, "mysqli"));
+
+        set_extension_enabled_in(&mut ini, "mysqli", false, false);
+        assert!(!is_extension_enabled(&ini, "mysqli"));
+        assert_eq!(ini.to_string(), STOCK_INI);
+    }
+
+    #[test]
+    fn enabling_a_missing_extension_appends_only_when_asked() {
+        let mut ini = IniFile::parse(STOCK_INI);
+        set_extension_enabled_in(&mut ini, "modulename", true, false);
+        assert!(!ini.is_modified());
+
+        set_extension_enabled_in(&mut ini, "intl", true, true);
+        assert!(is_extension_enabled(&ini, "intl"));
+    }
+}
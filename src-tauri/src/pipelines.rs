@@ -0,0 +1,423 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+use std::env;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use crate::db_provision::{existing_project_database, provision_project_database};
+use crate::env_file::update_env_file;
+use crate::filesystem::get_home;
+use crate::filesystem::get_node_path;
+use crate::scaffold::{composer_phar, php_binary, stream_command};
+use crate::store::read_projects;
+use crate::wordpress::{install_site, write_wp_config};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepAction {
+    // Creates .env from .env.example and sets the given keys
+    Env {
+        #[serde(default)]
+        values: BTreeMap<String, String>,
+    },
+    // Provisions (or reuses) the project's database and writes its DB_* keys
+    Database {
+        #[serde(default = "default_engine")]
+        engine: String,
+    },
+    Php { args: Vec<String> },
+    Composer { args: Vec<String> },
+    Npm { args: Vec<String> },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    // Writes wp-config.php with salts and the project's database
+    WpConfig,
+    // Runs `wp core install` and installs the requested plugins and themes
+    WpInstall,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PipelineStep {
+    pub id: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub action: StepAction,
+    // Skipped unless this path exists in the project, e.g. "package.json"
+    #[serde(default)]
+    pub when_exists: Option<String>,
+    // Skipped unless this option was passed as "true"
+    #[serde(default)]
+    pub when_option: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StepResult {
+    pub id: String,
+    pub label: String,
+    // "ok", "failed" or "skipped"
+    pub status: String,
+    pub error: Option<String>,
+    pub duration_ms: u128,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PipelineProgress {
+    pub project: String,
+    pub step: String,
+    pub line: String,
+    // Set on step transitions: "running", "ok", "failed", "skipped"
+    pub status: Option<String>,
+}
+
+// Everything a step needs to know about the project it runs in
+#[derive(Clone, Debug, Default)]
+pub struct PipelineContext {
+    pub path: PathBuf,
+    pub name: String,
+    pub php_version: Option<String>,
+    pub node_version: Option<String>,
+    pub port: Option<u16>,
+    pub domain: Option<String>,
+    // Free-form values from the create dialog, e.g. WordPress admin details
+    pub options: HashMap<String, String>,
+}
+
+fn default_engine() -> String {
+    "mysql".to_string()
+}
+
+fn pipelines_path() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("pipelines.json"))
+}
+
+fn step(id: &str, label: &str, action: StepAction, when_exists: Option<&str>) -> PipelineStep {
+    PipelineStep { id: id.into(), label: label.into(), action, when_exists: when_exists.map(str::to_string), when_option: None }
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| a.to_string()).collect()
+}
+
+fn npm_install() -> PipelineStep {
+    step("npm-install", "Install Node dependencies", StepAction::Npm { args: args(&["install"]) }, Some("package.json"))
+}
+
+fn builtin_pipeline(framework: &str) -> Vec<PipelineStep> {
+    match framework {
+        "laravel" => vec![
+            step("env", "Create .env", StepAction::Env {
+                values: BTreeMap::from([
+                    ("APP_NAME".to_string(), "{name}".to_string()),
+                    ("APP_URL".to_string(), "{url}".to_string()),
+                ]),
+            }, None),
+            // Before the database: a failed database step stops the pipeline, and the app still needs its key
+            step("key", "Generate application key", StepAction::Php { args: args(&["artisan", "key:generate", "--force"]) }, Some("artisan")),
+            step("database", "Create database", StepAction::Database { engine: default_engine() }, None),
+            step("migrate", "Run migrations", StepAction::Php { args: args(&["artisan", "migrate", "--force"]) }, Some("artisan")),
+            npm_install(),
+        ],
+        "wordpress" => vec![
+            step("wp-config", "Generate wp-config.php", StepAction::WpConfig, Some("wp-config-sample.php")),
+            PipelineStep {
+                when_option: Some("install".into()),
+                ..step("wp-install", "Install WordPress", StepAction::WpInstall, None)
+            },
+        ],
+        "symfony" | "react" | "svelte" | "node" | "vite" | "next" | "nuxt" | "astro" => vec![npm_install()],
+        _ => vec![],
+    }
+}
+
+fn load_user_pipelines() -> HashMap<String, Vec<PipelineStep>> {
+    pipelines_path().ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+// A user-defined pipeline replaces the built-in one for that framework
+pub fn pipeline_for(framework: &str) -> Vec<PipelineStep> {
+    load_user_pipelines().remove(framework).unwrap_or_else(|| builtin_pipeline(framework))
+}
+
+fn same_path(a: &str, b: &Path) -> bool {
+    Path::new(a.trim_end_matches(['/', '\\'])) == b
+}
+
+// Fills in name, versions and port from projects.json when the project is registered there
+pub fn context_for(project_path: &str) -> (PipelineContext, Option<String>) {
+    let path = PathBuf::from(project_path.trim_end_matches(['/', '\\']));
+    let mut ctx = PipelineContext {
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.clone(),
+        ..Default::default()
+    };
+
+    let project = read_projects().ok().and_then(|projects| {
+        projects.into_iter().find(|p| p.get("path").and_then(Value::as_str).map(|s| same_path(s, &path)).unwrap_or(false))
+    });
+    let Some(project) = project else { return (ctx, None) };
+
+    let text = |key: &str| project.get(key).and_then(Value::as_str).map(str::to_string);
+    if let Some(name) = text("name") { ctx.name = name; }
+    ctx.php_version = text("phpVersion");
+    ctx.node_version = text("nodeVersion");
+    ctx.domain = text("domain");
+    ctx.port = project.get("port").and_then(Value::as_u64).map(|p| p as u16);
+    (ctx, text("framework"))
+}
+
+pub fn project_url(ctx: &PipelineContext) -> String {
+    match (&ctx.domain, ctx.port) {
+        (Some(domain), _) if domain != "localhost" => format!("http://{}", domain),
+        (_, Some(port)) => format!("http://localhost:{}", port),
+        _ => "http://localhost".to_string(),
+    }
+}
+
+fn substitute(value: &str, ctx: &PipelineContext) -> String {
+    value.replace("{name}", &ctx.name).replace("{url}", &project_url(ctx)).replace("{path}", &ctx.path.to_string_lossy())
+}
+
+// Puts the project's PHP and Node first on PATH so scripts that shell out use the same versions
+pub fn prepend_path(cmd: &mut Command, dirs: &[PathBuf]) {
+    let separator = if cfg!(target_os = "windows") { ";" } else { ":" };
+    let mut parts: Vec<String> = dirs.iter().map(|d| d.to_string_lossy().to_string()).collect();
+    parts.push(env::var("PATH").unwrap_or_default());
+    cmd.env("PATH", parts.join(separator));
+}
+
+fn node_dir(ctx: &PipelineContext) -> Result<Option<PathBuf>, String> {
+    match ctx.node_version.as_deref() {
+        Some(v) if !v.is_empty() && v != "System" => get_node_path(v.to_string()).map(|p| Some(PathBuf::from(p))),
+        _ => Ok(None),
+    }
+}
+
+pub fn tool_dirs(ctx: &PipelineContext) -> Result<Vec<PathBuf>, String> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = node_dir(ctx)? { dirs.push(dir); }
+    if let Some(dir) = php_binary(ctx.php_version.as_deref())?.parent().filter(|d| !d.as_os_str().is_empty()) {
+        dirs.push(dir.to_path_buf());
+    }
+    Ok(dirs)
+}
+
+fn build_command(action: &StepAction, ctx: &PipelineContext) -> Result<Command, String> {
+    let php = php_binary(ctx.php_version.as_deref())?;
+    let mut cmd = match action {
+        StepAction::Php { args } => {
+            let mut c = Command::new(&php);
+            c.args(args.iter().map(|a| substitute(a, ctx)));
+            c
+        }
+        StepAction::Composer { args } => {
+            let mut c = Command::new(&php);
+            c.arg(composer_phar()?).args(args.iter().map(|a| substitute(a, ctx)));
+            c
+        }
+        StepAction::Npm { args } => {
+            let npm = if cfg!(target_os = "windows") { "npm.cmd" } else { "npm" };
+            let mut c = match node_dir(ctx)? {
+                Some(dir) => Command::new(dir.join(npm)),
+                None => Command::new(npm),
+            };
+            c.args(args.iter().map(|a| substitute(a, ctx)));
+            c
+        }
+        StepAction::Command { program, args } => {
+            let mut c = Command::new(substitute(program, ctx));
+            c.args(args.iter().map(|a| substitute(a, ctx)));
+            c
+        }
+        _ => return Err("Not a command step".to_string()),
+    };
+    cmd.current_dir(&ctx.path);
+    prepend_path(&mut cmd, &tool_dirs(ctx)?);
+    Ok(cmd)
+}
+
+async fn provision_database(engine: &str, ctx: &PipelineContext) -> Result<String, String> {
+    let project_path = ctx.path.to_string_lossy().to_string();
+
+    // Re-running reuses the database provisioned earlier instead of creating another
+    let (entry, message) = match existing_project_database(&project_path, None) {
+        Some(existing) => {
+            let message = format!("Using existing database {} on {}", existing.database, existing.instance);
+            (existing, message)
+        }
+        None => {
+            let created = provision_project_database(&project_path, engine, None).await?;
+            let message = format!("Created database {} on {}", created.database, created.instance);
+            (created, message)
+        }
+    };
+
+    let connection = if entry.engine == "mysql" { "mysql" } else { "pgsql" };
+    update_env_file(&ctx.path, &[
+        ("DB_CONNECTION", connection.to_string()),
+        ("DB_HOST", entry.host.clone()),
+        ("DB_PORT", entry.port.to_string()),
+        ("DB_DATABASE", entry.database.clone()),
+        ("DB_USERNAME", entry.username.clone()),
+        ("DB_PASSWORD", entry.password.clone()),
+    ])?;
+    Ok(message)
+}
+
+#[derive(Clone)]
+struct Reporter {
+    app: AppHandle,
+    project: String,
+}
+
+impl Reporter {
+    fn emit(&self, step: &str, line: impl Into<String>, status: Option<&str>) {
+        let _ = self.app.emit("post-create-progress", PipelineProgress {
+            project: self.project.clone(),
+            step: step.to_string(),
+            line: line.into(),
+            status: status.map(str::to_string),
+        });
+    }
+}
+
+async fn run_step(reporter: &Reporter, step: &PipelineStep, ctx: &PipelineContext) -> Result<(), String> {
+    match &step.action {
+        StepAction::Env { values } => {
+            let pairs: Vec<(&str, String)> = values.iter().map(|(k, v)| (k.as_str(), substitute(v, ctx))).collect();
+            update_env_file(&ctx.path, &pairs)?;
+            reporter.emit(&step.id, format!("Wrote {} keys to .env", pairs.len()), None);
+            Ok(())
+        }
+        StepAction::Database { engine } => {
+            let message = provision_database(engine, ctx).await?;
+            reporter.emit(&step.id, message, None);
+            Ok(())
+        }
+        StepAction::WpConfig => {
+            let message = write_wp_config(ctx).await?;
+            reporter.emit(&step.id, message, None);
+            Ok(())
+        }
+        StepAction::WpInstall => {
+            let r = reporter.clone();
+            let id = step.id.clone();
+            install_site(ctx, move |line| r.emit(&id, line, None)).await
+        }
+        action => {
+            let cmd = build_command(action, ctx)?;
+            let r = reporter.clone();
+            let id = step.id.clone();
+            stream_command(cmd, move |line| r.emit(&id, line, None)).await
+        }
+    }
+}
+
+// Runs the steps in order and stops at the first failure; later steps are reported as skipped
+pub async fn run_pipeline(app: &AppHandle, ctx: &PipelineContext, steps: &[PipelineStep]) -> Vec<StepResult> {
+    let reporter = Reporter { app: app.clone(), project: ctx.path.to_string_lossy().to_string() };
+    let mut results = Vec::new();
+    let mut failed = false;
+
+    for step in steps {
+        let started = Instant::now();
+        let skip_reason = if failed {
+            Some("an earlier step failed".to_string())
+        } else if let Some(file) = step.when_exists.as_ref().filter(|f| !ctx.path.join(f).exists()) {
+            Some(format!("{} not found", file))
+        } else {
+            step.when_option.as_ref()
+                .filter(|key| ctx.options.get(*key).map(String::as_str) != Some("true"))
+                .map(|key| format!("option '{}' not set", key))
+        };
+
+        let (status, error) = match skip_reason {
+            Some(reason) => ("skipped", Some(reason)),
+            None => {
+                reporter.emit(&step.id, format!("==> {}", step.label), Some("running"));
+                match run_step(&reporter, step, ctx).await {
+                    Ok(()) => ("ok", None),
+                    Err(e) => {
+                        failed = true;
+                        ("failed", Some(e))
+                    }
+                }
+            }
+        };
+
+        reporter.emit(&step.id, error.clone().unwrap_or_else(|| step.label.clone()), Some(status));
+        results.push(StepResult {
+            id: step.id.clone(),
+            label: step.label.clone(),
+            status: status.to_string(),
+            error,
+            duration_ms: started.elapsed().as_millis(),
+        });
+    }
+    results
+}
+
+#[tauri::command]
+pub fn get_post_create_pipeline(framework: String) -> Vec<PipelineStep> {
+    pipeline_for(&framework)
+}
+
+#[tauri::command]
+pub fn save_post_create_pipeline(framework: String, steps: Option<Vec<PipelineStep>>) -> Result<String, String> {
+    let mut pipelines = load_user_pipelines();
+    match steps {
+        Some(steps) => { pipelines.insert(framework.clone(), steps); }
+        None => { pipelines.remove(&framework); }
+    }
+    let data = serde_json::to_string_pretty(&pipelines).map_err(|e| e.to_string())?;
+    fs::write(pipelines_path()?, data).map_err(|e| e.to_string())?;
+    Ok(format!("Saved {} pipeline", framework))
+}
+
+// Runs the project's pipeline, or only the listed step ids when `steps` is given
+#[tauri::command]
+pub async fn run_post_create(
+    app: AppHandle,
+    project_path: String,
+    framework: Option<String>,
+    steps: Option<Vec<String>>,
+    options: Option<HashMap<String, String>>,
+) -> Result<Vec<StepResult>, String> {
+    let (mut ctx, project_framework) = context_for(&project_path);
+    ctx.options = options.unwrap_or_default();
+    if !ctx.path.is_dir() {
+        return Err("Project folder does not exist".to_string());
+    }
+    let framework = framework.or(project_framework).ok_or("Unknown framework; pass one explicitly")?;
+
+    let mut pipeline = pipeline_for(&framework);
+    if let Some(only) = &steps {
+        if let Some(unknown) = only.iter().find(|id| !pipeline.iter().any(|s| &s.id == *id)) {
+            return Err(format!("The {} pipeline has no step '{}'", framework, unknown));
+        }
+        pipeline.retain(|s| only.contains(&s.id));
+    }
+
+    Ok(run_pipeline(&app, &ctx, &pipeline).await)
+}
+
+// Context for a project that was just created and isn't in projects.json yet
+pub fn new_project_context(path: &Path, name: &str, options: &HashMap<String, String>) -> PipelineContext {
+    PipelineContext {
+        path: path.to_path_buf(),
+        name: name.to_string(),
+        php_version: options.get("php_version").cloned(),
+        node_version: options.get("node_version").cloned(),
+        options: options.clone(),
+        ..Default::default()
+    }
+}
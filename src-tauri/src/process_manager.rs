@@ -1,67 +1,91 @@
 use std::collections::HashMap;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
 use std::path::Path;
 use std::env;
 use std::net::{TcpStream, SocketAddr};
 use std::time::{Duration, Instant};
+use std::io::{BufRead, BufReader, Read};
 use std::thread;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
+use crate::project_ini::scan_dir_env;
+
+#[derive(Clone, Debug)]
+pub struct LaunchSpec {
+    pub bin_path: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    pub env_paths: Option<Vec<String>>,
+    pub port: Option<u16>,
+}
 
 pub struct ServiceState {
     pub pids: Mutex<HashMap<String, u32>>,
+    // How each running service was started, so it can be inspected or restarted
+    pub launches: Mutex<HashMap<String, LaunchSpec>>,
 }
 
 impl ServiceState {
     pub fn new() -> Self {
         Self {
             pids: Mutex::new(HashMap::new()),
+            launches: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+pub fn kill_pid(pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()]) 
+            .output();
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // Supervised workers lead their own process group, so their children (node under npm) stop too
+        let group = Command::new("kill").args(["--", &format!("-{}", pid)]).output();
+        if !group.map(|o| o.status.success()).unwrap_or(false) {
+            let _ = Command::new("kill").arg(pid.to_string()).output();
         }
     }
 }
 
+fn is_php_binary(bin_path: &str) -> bool {
+    Path::new(bin_path).file_stem()
+        .map(|s| matches!(s.to_string_lossy().to_lowercase().as_str(), "php" | "php-cgi"))
+        .unwrap_or(false)
+}
+
 fn is_port_open(port: u16) -> bool {
     let addr = format!("127.0.0.1:{}", port);
     TcpStream::connect_timeout(&addr.parse().unwrap(), Duration::from_millis(100)).is_ok()
 }
 
-#[tauri::command]
-pub async fn start_service(
-    state: State<'_, ServiceState>,
-    id: String,
-    bin_path: String,
-    args: Vec<String>,
-    cwd: Option<String>,
-    env_paths: Option<Vec<String>>,
-    port: Option<u16>,
-) -> Result<String, String> {
-    
-    {
-        let pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
-        if pids.contains_key(&id) {
-            return Err(format!("Service '{}' is already running.", id));
-        }
-    }
-
-    if let Some(p) = port {
-        if is_port_open(p) {
-            return Err(format!("Port {} is already in use by another application.", p));
-        }
-    }
+// The command a spec describes: its bin directory and env_paths on PATH, and project php.ini fragments for PHP
+pub fn command_for(spec: &LaunchSpec) -> Command {
+    let LaunchSpec { bin_path, args, cwd, env_paths, .. } = spec;
 
-    let mut command = Command::new(&bin_path);
-    command.args(&args);
+    let mut command = Command::new(bin_path);
+    command.args(args);
 
     if let Some(dir) = cwd {
         command.current_dir(dir);
+
+        // Project php.ini fragments are layered on top of the build's php.ini
+        if is_php_binary(bin_path) {
+            if let Some(scan_dir) = scan_dir_env(Path::new(dir)) {
+                command.env("PHP_INI_SCAN_DIR", scan_dir);
+            }
+        }
     }
 
     let current_path = env::var("PATH").unwrap_or_default();
     let mut new_path_parts = Vec::new();
     if let Some(paths) = env_paths {
-        for p in paths { new_path_parts.push(p); }
+        for p in paths { new_path_parts.push(p.clone()); }
     }
-    if let Some(parent_dir) = Path::new(&bin_path).parent() {
+    if let Some(parent_dir) = Path::new(bin_path).parent() {
         new_path_parts.push(parent_dir.to_string_lossy().to_string());
     }
     new_path_parts.push(current_path);
@@ -76,6 +100,40 @@ pub async fn start_service(
         command.creation_flags(CREATE_NO_WINDOW);
     }
 
+    command
+}
+
+#[tauri::command]
+pub async fn start_service(
+    state: State<'_, ServiceState>,
+    id: String,
+    bin_path: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    env_paths: Option<Vec<String>>,
+    port: Option<u16>,
+) -> Result<String, String> {
+    launch_service(&state, id, LaunchSpec { bin_path, args, cwd, env_paths, port })
+}
+
+pub fn launch_service(state: &ServiceState, id: String, spec: LaunchSpec) -> Result<String, String> {
+    let LaunchSpec { bin_path, port, .. } = spec.clone();
+
+    {
+        let pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
+        if pids.contains_key(&id) {
+            return Err(format!("Service '{}' is already running.", id));
+        }
+    }
+
+    if let Some(p) = port {
+        if is_port_open(p) {
+            return Err(format!("Port {} is already in use by another application.", p));
+        }
+    }
+
+    let mut command = command_for(&spec);
+
     let mut child = command.spawn()
         .map_err(|e| format!("Failed to start {}: {}", bin_path, e))?;
 
@@ -111,6 +169,9 @@ pub async fn start_service(
 
     let mut pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
     pids.insert(id.clone(), pid);
+    if let Ok(mut launches) = state.launches.lock() {
+        launches.insert(id.clone(), spec);
+    }
     
     println!("Started service: {} (PID: {})", id, pid);
     Ok(format!("Started {} (PID: {})", id, pid))
@@ -118,21 +179,197 @@ pub async fn start_service(
 
 #[tauri::command]
 pub fn stop_service(state: State<ServiceState>, id: String) -> Result<String, String> {
+    stop_service_by_id(&state, &id)
+}
+
+pub fn stop_service_by_id(state: &ServiceState, id: &str) -> Result<String, String> {
     let mut pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
 
-    if let Some(pid) = pids.remove(&id) {
-        #[cfg(target_os = "windows")]
-        {
-            let _ = Command::new("taskkill")
-                .args(["/F", "/T", "/PID", &pid.to_string()]) 
-                .output();
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            let _ = Command::new("kill").arg(pid.to_string()).output();
+    if let Some(pid) = pids.remove(id) {
+        kill_pid(pid);
+        if let Ok(mut launches) = state.launches.lock() {
+            launches.remove(id);
         }
         Ok(format!("Stopped service {}", id))
     } else {
         Err(format!("Service {} not found or not running", id))
     }
-}
\ No newline at end of file
+}
+
+// Stops a running service and starts it again with the arguments it was launched with
+pub fn restart_service_by_id(state: &ServiceState, id: &str) -> Result<String, String> {
+    let spec = state.launches.lock()
+        .map_err(|_| "Failed to lock state")?
+        .get(id)
+        .cloned()
+        .ok_or(format!("Service {} not found or not running", id))?;
+
+    stop_service_by_id(state, id)?;
+
+    if let Some(p) = spec.port {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while is_port_open(p) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    launch_service(state, id.to_string(), spec)
+}
+
+#[tauri::command]
+pub async fn restart_service(state: State<'_, ServiceState>, id: String) -> Result<String, String> {
+    restart_service_by_id(&state, &id)
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    on_line: impl Fn(String) + Send + 'static,
+) {
+    if let Some(pipe) = pipe {
+        thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                on_line(line);
+            }
+        });
+    }
+}
+
+fn spawn_piped<F>(spec: &LaunchSpec, on_line: &F) -> Result<Child, String>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    let mut command = command_for(spec);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", spec.bin_path, e))?;
+    forward_lines(child.stdout.take(), on_line.clone());
+    forward_lines(child.stderr.take(), on_line.clone());
+    Ok(child)
+}
+
+fn is_current(app: &AppHandle, id: &str, pid: u32) -> bool {
+    let state = app.state::<ServiceState>();
+    let current = state
+        .pids
+        .lock()
+        .map(|pids| pids.get(id) == Some(&pid))
+        .unwrap_or(false);
+    current
+}
+
+fn forget(app: &AppHandle, id: &str) {
+    let state = app.state::<ServiceState>();
+    if let Ok(mut pids) = state.pids.lock() {
+        pids.remove(id);
+    }
+    // Bound to a local like in is_current: a guard in the tail expression would outlive `state`
+    let launches = state.launches.lock();
+    if let Ok(mut launches) = launches {
+        launches.remove(id);
+    }
+}
+
+// Starts a long-lived worker with its output piped to `on_line`. A monitor thread notices when it exits:
+// stop_service removes the id first, so an exit while still registered is a crash and, with `restart`, is retried with backoff.
+pub fn launch_supervised<F>(
+    app: &AppHandle,
+    id: String,
+    spec: LaunchSpec,
+    restart: bool,
+    on_line: F,
+) -> Result<String, String>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    let state = app.state::<ServiceState>();
+    {
+        let pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
+        if pids.contains_key(&id) {
+            return Err(format!("Service '{}' is already running.", id));
+        }
+    }
+
+    let mut child = spawn_piped(&spec, &on_line)?;
+    let mut pid = child.id();
+    state
+        .pids
+        .lock()
+        .map_err(|_| "Failed to lock state")?
+        .insert(id.clone(), pid);
+    if let Ok(mut launches) = state.launches.lock() {
+        launches.insert(id.clone(), spec.clone());
+    }
+
+    let app = app.clone();
+    let worker = id.clone();
+    thread::spawn(move || {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            let started = Instant::now();
+            let status = child.wait();
+            if !is_current(&app, &worker, pid) {
+                break;
+            }
+            let status = status
+                .map(|s| s.to_string())
+                .unwrap_or_else(|e| e.to_string());
+            if !restart {
+                on_line(format!("Process exited ({})", status));
+                forget(&app, &worker);
+                break;
+            }
+
+            // A worker that stayed up for a while gets a fresh backoff
+            if started.elapsed() > Duration::from_secs(60) {
+                backoff = Duration::from_secs(1);
+            }
+            on_line(format!(
+                "Process exited ({}); restarting in {}s",
+                status,
+                backoff.as_secs()
+            ));
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+            if !is_current(&app, &worker, pid) {
+                break;
+            }
+
+            match spawn_piped(&spec, &on_line) {
+                Ok(next) => {
+                    let state = app.state::<ServiceState>();
+                    let replaced = match state.pids.lock() {
+                        Ok(mut pids) if pids.get(&worker) == Some(&pid) => {
+                            pids.insert(worker.clone(), next.id());
+                            true
+                        }
+                        _ => false,
+                    };
+                    if !replaced {
+                        // Stopped while the replacement was starting
+                        kill_pid(next.id());
+                        break;
+                    }
+                    pid = next.id();
+                    child = next;
+                    println!("Restarted worker: {} (PID: {})", worker, pid);
+                }
+                Err(e) => {
+                    on_line(e);
+                    forget(&app, &worker);
+                    break;
+                }
+            }
+        }
+    });
+
+    println!("Started worker: {} (PID: {})", id, pid);
+    Ok(format!("Started {} (PID: {})", id, pid))
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::php_ini::{validate_setting, IniDirective, IniFile};
+
+const OVERRIDES_FILE: &str = "99-project.ini";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EffectiveSetting {
+    pub key: String,
+    pub value: String,
+    // "default", "global" or "project"
+    pub layer: String,
+    pub source: String,
+}
+
+// Per-project fragments live in the project itself so they travel with it
+pub fn project_scan_dir(project_path: &Path) -> PathBuf {
+    project_path.join(".stackmanager").join("php.d")
+}
+
+// Value for PHP_INI_SCAN_DIR; the leading separator keeps the build's own scan dir in the list
+pub fn scan_dir_env(project_path: &Path) -> Option<String> {
+    let dir = project_scan_dir(project_path);
+    if !dir.is_dir() { return None; }
+    let separator = if cfg!(target_os = "windows") { ";" } else { ":" };
+    Some(format!("{}{}", separator, dir.to_string_lossy()))
+}
+
+// Fragments in the order PHP loads them (alphabetical)
+fn fragment_files(project_path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(project_scan_dir(project_path))
+        .map(|entries| entries.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|x| x == "ini").unwrap_or(false))
+            .collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn overrides_path(project_path: &str) -> PathBuf {
+    project_scan_dir(Path::new(project_path)).join(OVERRIDES_FILE)
+}
+
+pub fn load_overrides(project_path: &str) -> Result<IniFile, String> {
+    let path = overrides_path(project_path);
+    if path.exists() { IniFile::load(&path) } else { Ok(IniFile::parse("; StackManager project overrides\n")) }
+}
+
+pub fn save_overrides(project_path: &str, ini: &IniFile) -> Result<(), String> {
+    if !Path::new(project_path).is_dir() { return Err("Project folder does not exist".to_string()); }
+    let path = overrides_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    ini.save(&path)
+}
+
+#[tauri::command]
+pub fn get_project_php_overrides(project_path: String) -> Result<Vec<IniDirective>, String> {
+    let mut directives = Vec::new();
+    for file in fragment_files(Path::new(&project_path)) {
+        directives.extend(IniFile::load(&file)?.directives());
+    }
+    Ok(directives)
+}
+
+#[tauri::command]
+pub fn set_project_php_override(project_path: String, key: String, value: String) -> Result<String, String> {
+    let key = key.trim();
+    let value = value.trim();
+    validate_setting(key, value)?;
+
+    let mut ini = load_overrides(&project_path)?;
+    ini.set(key, value);
+    save_overrides(&project_path, &ini)?;
+    Ok(format!("Project override {} = {}", key, value))
+}
+
+#[tauri::command]
+pub fn unset_project_php_override(project_path: String, key: String) -> Result<String, String> {
+    let mut ini = load_overrides(&project_path)?;
+    if ini.unset(key.trim()) {
+        save_overrides(&project_path, &ini)?;
+        Ok(format!("Removed project override {}", key))
+    } else {
+        Ok(format!("{} is not overridden", key))
+    }
+}
+
+// Built-in values, read from PHP itself with all ini files disabled
+fn php_defaults(bin_dir: &Path) -> BTreeMap<String, String> {
+    let php = if cfg!(target_os = "windows") { bin_dir.join("php.exe") } else { bin_dir.join("php") };
+    let mut command = Command::new(php);
+    command.args(["-n", "-r", "foreach (ini_get_all(null, false) as $k => $v) { echo $k, '=', $v, PHP_EOL; }"]);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut defaults = BTreeMap::new();
+    if let Ok(output) = command.output() {
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some((k, v)) = line.split_once('=') {
+                defaults.insert(k.to_string(), v.to_string());
+            }
+        }
+    }
+    defaults
+}
+
+#[tauri::command]
+pub fn get_effective_php_config(bin_path_dir: String, project_path: Option<String>) -> Result<Vec<EffectiveSetting>, String> {
+    let bin_dir = PathBuf::from(&bin_path_dir);
+    let mut merged: BTreeMap<String, EffectiveSetting> = BTreeMap::new();
+
+    for (key, value) in php_defaults(&bin_dir) {
+        merged.insert(key.clone(), EffectiveSetting { key, value, layer: "default".to_string(), source: "built-in".to_string() });
+    }
+
+    let mut layers = vec![("global", bin_dir.join("php.ini"))];
+    if let Some(project) = &project_path {
+        layers.extend(fragment_files(Path::new(project)).into_iter().map(|f| ("project", f)));
+    }
+
+    for (layer, file) in layers {
+        if !file.exists() { continue; }
+        for directive in IniFile::load(&file)?.directives() {
+            // Multi-valued; list_php_extensions reports these
+            if directive.key == "extension" || directive.key == "zend_extension" { continue; }
+            merged.insert(directive.key.clone(), EffectiveSetting {
+                key: directive.key,
+                value: directive.value,
+                layer: layer.to_string(),
+                source: file.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    Ok(merged.into_values().collect())
+}
@@ -0,0 +1,399 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use crate::downloader::{node_release_versions, resolve_node_version};
+use crate::filesystem::get_services;
+use crate::frameworks::read_json;
+use crate::network;
+use crate::pipelines::context_for;
+use crate::shim::get_active_version;
+use crate::versions::{parse_folder_version, php_release_versions};
+
+type Version = (u32, u32, u32);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RuntimeRequirement {
+    // Where the constraint came from, e.g. ".nvmrc" or "composer.json require.php"
+    pub source: String,
+    pub constraint: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RuntimeResolution {
+    pub runtime: String,
+    pub requirements: Vec<RuntimeRequirement>,
+    // Installed folder the project uses now, following the global link for "Global"/"System"
+    pub current: Option<String>,
+    pub current_satisfies: bool,
+    // Newest installed folder that satisfies every requirement
+    pub selected: Option<String>,
+    // Exact release to download when nothing installed matches
+    pub download: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectRuntimes {
+    pub php: Option<RuntimeResolution>,
+    pub node: Option<RuntimeResolution>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Flavor {
+    Composer,
+    Npm,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    Eq,
+    Ne,
+}
+
+// Any of the alternatives, each a list of comparators that must all hold
+struct Constraint {
+    alternatives: Vec<Vec<(Op, Version)>>,
+}
+
+impl Constraint {
+    fn matches(&self, v: Version) -> bool {
+        self.alternatives.iter().any(|all| all.iter().all(|(op, bound)| match op {
+            Op::Ge => v >= *bound,
+            Op::Gt => v > *bound,
+            Op::Le => v <= *bound,
+            Op::Lt => v < *bound,
+            Op::Eq => v == *bound,
+            Op::Ne => v != *bound,
+        }))
+    }
+}
+
+// "v8.2.1-RC1@dev" -> [8, 2, 1]; stops at the first wildcard, so "8.x" -> [8]
+fn parse_parts(text: &str) -> Option<Vec<u32>> {
+    let text = text.trim().trim_start_matches(['v', 'V']);
+    let text = text.split('@').next()?.split('-').next()?.split('+').next()?;
+    let mut parts = Vec::new();
+    for part in text.split('.') {
+        if matches!(part, "*" | "x" | "X") {
+            break;
+        }
+        parts.push(part.parse().ok()?);
+    }
+    if parts.len() > 3 { parts.truncate(3); }
+    Some(parts)
+}
+
+fn lower(parts: &[u32]) -> Version {
+    (parts.first().copied().unwrap_or(0), parts.get(1).copied().unwrap_or(0), parts.get(2).copied().unwrap_or(0))
+}
+
+// First version past an x-range: [8, 2] -> 8.3.0, [8] -> 9.0.0
+fn next_after(parts: &[u32]) -> Version {
+    let (major, minor, patch) = lower(parts);
+    match parts.len() {
+        0 | 1 => (major + 1, 0, 0),
+        2 => (major, minor + 1, 0),
+        _ => (major, minor, patch + 1),
+    }
+}
+
+fn comparator(token: &str, flavor: Flavor) -> Option<Vec<(Op, Version)>> {
+    let ops = [">=", "<=", "!=", "==", ">", "<", "=", "^", "~"];
+    let (op, rest) = ops.iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    let parts = parse_parts(rest)?;
+    let exact = parts.len() == 3;
+    let low = lower(&parts);
+    if parts.is_empty() {
+        return Some(vec![]);
+    }
+
+    Some(match op {
+        ">=" => vec![(Op::Ge, low)],
+        ">" if exact => vec![(Op::Gt, low)],
+        ">" => vec![(Op::Ge, next_after(&parts))],
+        "<" => vec![(Op::Lt, low)],
+        "<=" if exact => vec![(Op::Le, low)],
+        "<=" => vec![(Op::Lt, next_after(&parts))],
+        "!=" if exact => vec![(Op::Ne, low)],
+        "!=" => vec![],
+        "^" => {
+            let upper = match low {
+                (0, 0, patch) if exact => (0, 0, patch + 1),
+                (0, minor, _) if parts.len() > 1 => (0, minor + 1, 0),
+                (major, _, _) => (major + 1, 0, 0),
+            };
+            vec![(Op::Ge, low), (Op::Lt, upper)]
+        }
+        // Composer's ~8.1 allows 8.x from 8.1 on; npm's only allows 8.1.x
+        "~" if flavor == Flavor::Composer && parts.len() == 2 => vec![(Op::Ge, low), (Op::Lt, (low.0 + 1, 0, 0))],
+        "~" if parts.len() == 1 => vec![(Op::Ge, low), (Op::Lt, (low.0 + 1, 0, 0))],
+        "~" => vec![(Op::Ge, low), (Op::Lt, (low.0, low.1 + 1, 0))],
+        _ if exact => vec![(Op::Eq, low)],
+        _ => vec![(Op::Ge, low), (Op::Lt, next_after(&parts))],
+    })
+}
+
+// Composer and npm range syntax: "^8.1 || ^8.2", ">=7.4 <8.3", "8.1.*", "18 - 20", ">= 18.17"
+fn parse_constraint(text: &str, flavor: Flavor) -> Option<Constraint> {
+    let mut alternatives = Vec::new();
+    for alternative in text.replace("||", "|").split('|') {
+        // Glue "<= 8.2" back into one token
+        let mut tokens: Vec<String> = Vec::new();
+        for token in alternative.split([' ', ',']).filter(|t| !t.is_empty()) {
+            match tokens.last_mut() {
+                Some(last) if last.chars().all(|c| "<>=!^~".contains(c)) => last.push_str(token),
+                _ => tokens.push(token.to_string()),
+            }
+        }
+
+        let mut all = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if tokens.get(i + 1).map(String::as_str) == Some("-") && i + 2 < tokens.len() {
+                let (from, to) = (parse_parts(&tokens[i])?, parse_parts(&tokens[i + 2])?);
+                all.push((Op::Ge, lower(&from)));
+                all.push(if to.len() == 3 { (Op::Le, lower(&to)) } else { (Op::Lt, next_after(&to)) });
+                i += 3;
+            } else {
+                all.extend(comparator(&tokens[i], flavor)?);
+                i += 1;
+            }
+        }
+        alternatives.push(all);
+    }
+    Some(Constraint { alternatives })
+}
+
+// Version files pin a line, not a build: "8.2.12" and "8.2" both accept any 8.2.x
+fn pin_constraint(text: &str) -> Option<Constraint> {
+    let parts = parse_parts(text).filter(|p| !p.is_empty())?;
+    let line = &parts[..parts.len().min(2)];
+    Some(Constraint { alternatives: vec![vec![(Op::Ge, lower(line)), (Op::Lt, next_after(line))]] })
+}
+
+fn first_line(path: &Path) -> Option<String> {
+    let data = fs::read_to_string(path).ok()?;
+    data.lines().map(str::trim).find(|l| !l.is_empty() && !l.starts_with('#')).map(str::to_string)
+}
+
+fn requirement(source: &str, constraint: &str) -> RuntimeRequirement {
+    RuntimeRequirement { source: source.to_string(), constraint: constraint.to_string() }
+}
+
+fn php_requirements(root: &Path) -> Vec<(RuntimeRequirement, Constraint)> {
+    let mut found = Vec::new();
+    if let Some(pin) = first_line(&root.join(".php-version")) {
+        if let Some(c) = pin_constraint(&pin) { found.push((requirement(".php-version", &pin), c)); }
+    }
+
+    let composer = read_json(&root.join("composer.json"));
+    let text = |path: &[&str]| {
+        let mut value = composer.as_ref()?;
+        for key in path { value = value.get(key)?; }
+        value.as_str().map(str::to_string)
+    };
+    if let Some(platform) = text(&["config", "platform", "php"]) {
+        if let Some(c) = pin_constraint(&platform) { found.push((requirement("composer.json config.platform.php", &platform), c)); }
+    }
+    if let Some(required) = text(&["require", "php"]) {
+        if let Some(c) = parse_constraint(&required, Flavor::Composer) { found.push((requirement("composer.json require.php", &required), c)); }
+    }
+    found
+}
+
+fn is_node_alias(spec: &str) -> bool {
+    let spec = spec.to_lowercase();
+    spec.starts_with("lts") || matches!(spec.as_str(), "node" | "stable" | "latest" | "current")
+}
+
+// nvm aliases like "lts/iron" are looked up in the release index and then accept that major line
+async fn node_pin(spec: &str) -> Option<Constraint> {
+    if spec.eq_ignore_ascii_case("system") {
+        return None;
+    }
+    if !is_node_alias(spec) {
+        return pin_constraint(spec);
+    }
+    let client = network::build_client().ok()?;
+    let exact = resolve_node_version(&client, spec).await.ok()?;
+    pin_constraint(exact.split('.').next()?)
+}
+
+async fn node_requirements(root: &Path) -> Vec<(RuntimeRequirement, Constraint)> {
+    let mut found = Vec::new();
+    for file in [".nvmrc", ".node-version"] {
+        if let Some(pin) = first_line(&root.join(file)) {
+            if let Some(c) = node_pin(&pin).await { found.push((requirement(file, &pin), c)); }
+        }
+    }
+    let engine = read_json(&root.join("package.json"))
+        .and_then(|p| p.get("engines")?.get("node")?.as_str().map(str::to_string));
+    if let Some(engine) = engine {
+        if let Some(c) = parse_constraint(&engine, Flavor::Npm) { found.push((requirement("package.json engines.node", &engine), c)); }
+    }
+    found
+}
+
+fn installed(prefix: &str) -> Vec<(String, Version)> {
+    let mut found: Vec<(String, Version)> = get_services().unwrap_or_default().into_iter()
+        .filter_map(|name| parse_folder_version(&name, prefix).map(|v| (name, v)))
+        .collect();
+    found.sort_by_key(|(_, v)| std::cmp::Reverse(*v));
+    found
+}
+
+fn version_string(v: Version) -> String {
+    format!("{}.{}.{}", v.0, v.1, v.2)
+}
+
+// "Global"/"System" mean whatever the shared bin link points at
+fn current_folder(project_setting: Option<&str>, service: &str, default: &str) -> Option<String> {
+    match project_setting {
+        Some(v) if !v.is_empty() && v != default => Some(v.to_string()),
+        _ => get_active_version(service.to_string()).ok().filter(|v| v != "Not Set" && v != "Unknown"),
+    }
+}
+
+async fn resolve(
+    runtime: &str,
+    prefix: &str,
+    requirements: Vec<(RuntimeRequirement, Constraint)>,
+    current: Option<String>,
+) -> Option<RuntimeResolution> {
+    if requirements.is_empty() {
+        return None;
+    }
+    let satisfies = |v: Version| requirements.iter().all(|(_, c)| c.matches(v));
+    let label = if runtime == "php" { "PHP" } else { "Node.js" };
+
+    let current_satisfies = current.as_deref()
+        .and_then(|folder| parse_folder_version(folder, prefix))
+        .map(satisfies)
+        .unwrap_or(false);
+    let selected = installed(prefix).into_iter().find(|(_, v)| satisfies(*v)).map(|(name, _)| name);
+
+    let mut download = None;
+    let message = if let Some(folder) = &selected {
+        format!("{} satisfies the project's {} requirements", folder, label)
+    } else {
+        let releases = if runtime == "php" {
+            php_release_versions().await
+        } else {
+            match network::build_client() {
+                Ok(client) => node_release_versions(&client).await,
+                Err(e) => Err(e),
+            }
+        };
+        match releases {
+            Ok(versions) => {
+                let mut candidates: Vec<Version> = versions.iter()
+                    .filter_map(|v| parse_parts(v).filter(|p| p.len() == 3).map(|p| lower(&p)))
+                    .filter(|v| satisfies(*v))
+                    .collect();
+                candidates.sort();
+                download = candidates.last().copied().map(version_string);
+                match &download {
+                    Some(v) => format!("No installed {} matches; {} {} can be downloaded", label, label, v),
+                    None => format!("No {} release satisfies all of the project's requirements", label),
+                }
+            }
+            Err(e) => format!("No installed {} matches, and releases could not be checked: {}", label, e),
+        }
+    };
+
+    Some(RuntimeResolution {
+        runtime: runtime.to_string(),
+        requirements: requirements.into_iter().map(|(r, _)| r).collect(),
+        current,
+        current_satisfies,
+        selected,
+        download,
+        message,
+    })
+}
+
+pub async fn resolve_runtimes(project_path: &str) -> ProjectRuntimes {
+    let (ctx, _) = context_for(project_path);
+    let php_current = current_folder(ctx.php_version.as_deref(), "php", "Global");
+    let node_current = current_folder(ctx.node_version.as_deref(), "node", "System");
+
+    ProjectRuntimes {
+        php: resolve("php", "php-", php_requirements(&ctx.path), php_current).await,
+        node: resolve("node", "node-v", node_requirements(&ctx.path).await, node_current).await,
+    }
+}
+
+// Checked when a project is imported and before it starts; the frontend applies `selected` or offers `download`
+#[tauri::command]
+pub async fn resolve_project_runtimes(project_path: String) -> Result<ProjectRuntimes, String> {
+    if !Path::new(&project_path).is_dir() {
+        return Err(format!("Project folder not found: {}", project_path));
+    }
+    Ok(resolve_runtimes(&project_path).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(constraint: &str, flavor: Flavor, version: Version) -> bool {
+        parse_constraint(constraint, flavor).expect(constraint).matches(version)
+    }
+
+    #[test]
+    fn caret_below_one_stays_on_the_minor_or_patch() {
+        assert!(allows("^0.2", Flavor::Npm, (0, 2, 9)));
+        assert!(!allows("^0.2", Flavor::Npm, (0, 3, 0)));
+        assert!(allows("^0.0.3", Flavor::Npm, (0, 0, 3)));
+        assert!(!allows("^0.0.3", Flavor::Npm, (0, 0, 4)));
+        assert!(allows("^0", Flavor::Composer, (0, 9, 0)));
+        assert!(!allows("^0", Flavor::Composer, (1, 0, 0)));
+    }
+
+    #[test]
+    fn tilde_differs_between_composer_and_npm() {
+        assert!(allows("~8.1", Flavor::Composer, (8, 4, 0)));
+        assert!(!allows("~8.1", Flavor::Composer, (9, 0, 0)));
+        assert!(allows("~8.1", Flavor::Npm, (8, 1, 7)));
+        assert!(!allows("~8.1", Flavor::Npm, (8, 4, 0)));
+        assert!(!allows("~8.1.2", Flavor::Composer, (8, 2, 0)));
+    }
+
+    #[test]
+    fn hyphen_range_includes_the_whole_upper_major() {
+        assert!(allows("18 - 20", Flavor::Npm, (18, 0, 0)));
+        assert!(allows("18 - 20", Flavor::Npm, (20, 5, 0)));
+        assert!(!allows("18 - 20", Flavor::Npm, (21, 0, 0)));
+        assert!(!allows("18 - 20", Flavor::Npm, (17, 9, 0)));
+    }
+
+    #[test]
+    fn space_separated_comparators_must_all_hold() {
+        assert!(allows(">=7.4 <8.3", Flavor::Composer, (7, 4, 0)));
+        assert!(allows(">=7.4 <8.3", Flavor::Composer, (8, 2, 12)));
+        assert!(!allows(">=7.4 <8.3", Flavor::Composer, (8, 3, 0)));
+        assert!(!allows(">=7.4 <8.3", Flavor::Composer, (7, 3, 33)));
+        assert!(allows(">= 18.17", Flavor::Npm, (18, 17, 0)));
+    }
+
+    #[test]
+    fn wildcards_and_alternatives() {
+        assert!(allows("8.1.*", Flavor::Composer, (8, 1, 27)));
+        assert!(!allows("8.1.*", Flavor::Composer, (8, 2, 0)));
+        assert!(allows("^7.4 || ^8.1", Flavor::Composer, (8, 3, 0)));
+        assert!(!allows("^7.4 || ^8.1", Flavor::Composer, (8, 0, 30)));
+    }
+
+    #[test]
+    fn version_files_pin_a_minor_line() {
+        let pin = pin_constraint("8.2.12").unwrap();
+        assert!(pin.matches((8, 2, 0)));
+        assert!(!pin.matches((8, 3, 0)));
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use crate::composer::{ensure_php_extensions, init_composer};
+use crate::downloader::{extract_archive, fetch_cached};
+use crate::filesystem::get_home;
+use crate::network;
+use crate::wordpress::download_url;
+use crate::pipelines::{new_project_context, pipeline_for, run_pipeline};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateSource {
+    // `composer create-project <package>`
+    Composer { package: String },
+    Git {
+        url: String,
+        #[serde(default)]
+        branch: Option<String>,
+    },
+    // `versioned_url` is used instead of `url` when a version is given; `{version}` is substituted
+    Archive {
+        url: String,
+        #[serde(default)]
+        versioned_url: Option<String>,
+    },
+    Local { path: String },
+    // wordpress.org release for the `version` and `locale` options
+    Wordpress,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateParam {
+    pub key: String,
+    pub label: String,
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub choices: Vec<String>,
+}
+
+// `program` is "php", "composer" or anything on PATH; `{name}` and `{<param>}` are substituted in args
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateStep {
+    pub label: String,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub framework: String,
+    pub source: TemplateSource,
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
+    #[serde(default)]
+    pub post_create: Vec<TemplateStep>,
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProjectProgress {
+    // "<template>:<project>", so concurrent creations can be told apart
+    pub id: String,
+    pub template: String,
+    pub project: String,
+    pub line: String,
+}
+
+#[derive(Clone)]
+pub struct Progress {
+    app: AppHandle,
+    template: String,
+    project: String,
+}
+
+impl Progress {
+    pub fn new(app: &AppHandle, template: &str, project: &str) -> Self {
+        Progress { app: app.clone(), template: template.to_string(), project: project.to_string() }
+    }
+
+    pub fn line(&self, line: impl Into<String>) {
+        let _ = self.app.emit("create-project-progress", ProjectProgress {
+            id: format!("{}:{}", self.template, self.project),
+            template: self.template.clone(),
+            project: self.project.clone(),
+            line: line.into(),
+        });
+    }
+}
+
+fn templates_path() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("templates.json"))
+}
+
+fn version_params() -> Vec<TemplateParam> {
+    vec![
+        TemplateParam { key: "version".into(), label: "Version constraint".into(), default: None, choices: vec![] },
+        TemplateParam {
+            key: "stability".into(),
+            label: "Minimum stability".into(),
+            default: Some("stable".into()),
+            choices: ["stable", "RC", "beta", "alpha", "dev"].iter().map(|s| s.to_string()).collect(),
+        },
+    ]
+}
+
+fn wordpress_params() -> Vec<TemplateParam> {
+    let param = |key: &str, label: &str, default: Option<&str>| TemplateParam {
+        key: key.into(),
+        label: label.into(),
+        default: default.map(str::to_string),
+        choices: vec![],
+    };
+    vec![
+        param("version", "WordPress version", None),
+        param("locale", "Locale (e.g. de_DE)", None),
+        TemplateParam {
+            choices: vec!["true".into(), "false".into()],
+            ..param("install", "Run the installer", Some("false"))
+        },
+        param("site_title", "Site title", None),
+        param("admin_user", "Admin user", Some("admin")),
+        param("admin_email", "Admin email", None),
+        param("admin_password", "Admin password", None),
+        param("plugins", "Plugins (comma separated slugs)", None),
+        param("themes", "Themes (comma separated slugs; the first is activated)", None),
+    ]
+}
+
+fn builtin_templates() -> Vec<ProjectTemplate> {
+    vec![
+        ProjectTemplate {
+            id: "laravel".into(),
+            name: "Laravel".into(),
+            framework: "laravel".into(),
+            source: TemplateSource::Composer { package: "laravel/laravel".into() },
+            params: version_params(),
+            post_create: vec![],
+            builtin: true,
+        },
+        ProjectTemplate {
+            id: "symfony".into(),
+            name: "Symfony".into(),
+            framework: "symfony".into(),
+            source: TemplateSource::Composer { package: "symfony/skeleton".into() },
+            params: version_params(),
+            post_create: vec![],
+            builtin: true,
+        },
+        ProjectTemplate {
+            id: "wordpress".into(),
+            name: "WordPress".into(),
+            framework: "wordpress".into(),
+            source: TemplateSource::Wordpress,
+            params: wordpress_params(),
+            post_create: vec![],
+            builtin: true,
+        },
+    ]
+}
+
+fn load_user_templates() -> Vec<ProjectTemplate> {
+    templates_path().ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_user_templates(templates: &[ProjectTemplate]) -> Result<(), String> {
+    let path = templates_path()?;
+    let data = serde_json::to_string_pretty(templates).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+pub fn find_template(id: &str) -> Result<ProjectTemplate, String> {
+    builtin_templates().into_iter()
+        .chain(load_user_templates())
+        .find(|t| t.id == id)
+        .ok_or(format!("Unknown project template: {}", id))
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.starts_with('.') || name.contains(['/', '\\', ':']) {
+        return Err(format!("Invalid project name: {}", name));
+    }
+    Ok(())
+}
+
+fn exe(name: &str) -> String {
+    if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() }
+}
+
+// The PHP binary of an installed service folder, or the global one for None/"Global"
+pub fn php_binary(php_version: Option<&str>) -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    let root = match php_version {
+        Some(v) if !v.is_empty() && v != "Global" => home.join(".stackmanager").join("services").join(v),
+        _ => home.join(".stackmanager").join("bin").join("php"),
+    };
+
+    let mut dirs = vec![root.clone(), root.join("bin")];
+    if let Ok(entries) = fs::read_dir(&root) {
+        for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+            dirs.push(entry.path());
+            dirs.push(entry.path().join("bin"));
+        }
+    }
+    if let Some(found) = dirs.iter().map(|d| d.join(exe("php"))).find(|p| p.is_file()) {
+        return Ok(found);
+    }
+
+    match php_version {
+        Some(v) if !v.is_empty() && v != "Global" => Err(format!("PHP {} is not installed", v)),
+        _ => Ok(PathBuf::from(exe("php"))),
+    }
+}
+
+pub fn composer_phar() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("bin").join("composer.phar"))
+}
+
+// Streams stdout and stderr line by line, then waits for the exit status
+pub async fn stream_command<F>(cmd: Command, on_line: F) -> Result<(), String>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    stream_command_with_input(cmd, None, on_line).await
+}
+
+// Same, writing `input` to stdin first; for secrets that must not show up in the process list
+pub async fn stream_command_with_input<F>(mut cmd: Command, input: Option<String>, on_line: F) -> Result<(), String>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    if input.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start {:?}: {}", cmd.get_program(), e))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // Dropping stdin afterwards closes it, so a prompt that wants more sees EOF instead of waiting
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    let on_out = on_line.clone();
+    let out = std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) { on_out(line); }
+    });
+    let err = std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) { on_line(line); }
+    });
+
+    let status = tauri::async_runtime::spawn_blocking(move || child.wait())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let _ = out.join();
+    let _ = err.join();
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{:?} exited with {}", cmd.get_program(), status))
+    }
+}
+
+pub async fn run_streamed(progress: &Progress, cmd: Command) -> Result<(), String> {
+    let progress = progress.clone();
+    stream_command(cmd, move |line| progress.line(line)).await
+}
+
+fn substitute(value: &str, name: &str, options: &HashMap<String, String>) -> String {
+    let mut result = value.replace("{name}", name);
+    for (key, val) in options {
+        result = result.replace(&format!("{{{}}}", key), val);
+    }
+    result
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
+    for entry in fs::read_dir(from).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            fs::copy(&path, &target).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+async fn from_composer(progress: &Progress, package: &str, name: &str, parent: &Path, options: &HashMap<String, String>) -> Result<(), String> {
+    init_composer().await?;
+    let php = php_binary(options.get("php_version").map(String::as_str))?;
+    if let Some(php_dir) = php.parent().filter(|d| d.join("php.ini").exists() || d.join("php.ini-development").exists()) {
+        ensure_php_extensions(&php_dir.to_path_buf())?;
+    }
+
+    let package = match options.get("version").filter(|v| !v.is_empty()) {
+        Some(version) => format!("{}:{}", package, version),
+        None => package.to_string(),
+    };
+
+    let mut cmd = Command::new(&php);
+    cmd.arg(composer_phar()?)
+        .args(["create-project", &package, name, "--prefer-dist", "--no-interaction"])
+        .current_dir(parent);
+    if let Some(stability) = options.get("stability").filter(|s| !s.is_empty()) {
+        cmd.arg(format!("--stability={}", stability));
+    }
+    run_streamed(progress, cmd).await
+}
+
+async fn from_git(progress: &Progress, url: &str, branch: Option<&str>, name: &str, parent: &Path) -> Result<(), String> {
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--depth", "1"]).current_dir(parent);
+    if let Some(branch) = branch {
+        cmd.args(["--branch", branch]);
+    }
+    cmd.args([url, name]);
+    run_streamed(progress, cmd).await?;
+
+    // A template's history doesn't belong to the new project
+    let _ = fs::remove_dir_all(parent.join(name).join(".git"));
+    Ok(())
+}
+
+async fn from_archive(progress: &Progress, url: &str, target: &Path) -> Result<(), String> {
+    progress.line(format!("Downloading {}...", url));
+    let client = network::build_client()?;
+    let archive = fetch_cached(&client, url).await?;
+
+    progress.line("Extracting files...");
+    // Extract next to the target so nothing collides with folders already in the parent
+    let staging = target.with_file_name(format!(".{}.extracting", target.file_name().unwrap_or_default().to_string_lossy()));
+    let _ = fs::remove_dir_all(&staging);
+    let moved = move_extracted(&archive, &staging, target);
+    // Gone on every path, so a failed attempt leaves nothing behind in the parent folder
+    let _ = fs::remove_dir_all(&staging);
+    moved
+}
+
+fn move_extracted(archive: &Path, staging: &Path, target: &Path) -> Result<(), String> {
+    extract_archive(archive, staging)?;
+
+    // Most archives wrap everything in a single top-level folder (wordpress/, project-main/)
+    let entries: Vec<PathBuf> = fs::read_dir(staging).map_err(|e| e.to_string())?.flatten().map(|e| e.path()).collect();
+    let root = match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => staging.to_path_buf(),
+    };
+    fs::rename(&root, target).map_err(|e| format!("Failed to move extracted files: {}", e))
+}
+
+pub async fn run_template_steps(progress: &Progress, steps: &[TemplateStep], project: &Path, name: &str, options: &HashMap<String, String>) -> Result<(), String> {
+    for step in steps {
+        progress.line(format!("==> {}", step.label));
+        let args: Vec<String> = step.args.iter().map(|a| substitute(a, name, options)).collect();
+
+        let mut cmd = match step.program.as_str() {
+            "php" => Command::new(php_binary(options.get("php_version").map(String::as_str))?),
+            "composer" => {
+                let mut c = Command::new(php_binary(options.get("php_version").map(String::as_str))?);
+                c.arg(composer_phar()?);
+                c
+            }
+            program => Command::new(program),
+        };
+        cmd.args(&args).current_dir(project);
+        run_streamed(progress, cmd).await.map_err(|e| format!("Step '{}' failed: {}", step.label, e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_project_templates() -> Vec<ProjectTemplate> {
+    builtin_templates().into_iter().chain(load_user_templates()).collect()
+}
+
+#[tauri::command]
+pub fn save_project_template(template: ProjectTemplate) -> Result<String, String> {
+    if template.id.trim().is_empty() {
+        return Err("Template id is required".to_string());
+    }
+    if builtin_templates().iter().any(|t| t.id == template.id) {
+        return Err(format!("'{}' is a built-in template", template.id));
+    }
+
+    let mut templates = load_user_templates();
+    templates.retain(|t| t.id != template.id);
+    let id = template.id.clone();
+    templates.push(template);
+    save_user_templates(&templates)?;
+    Ok(format!("Saved template {}", id))
+}
+
+#[tauri::command]
+pub fn delete_project_template(id: String) -> Result<String, String> {
+    let mut templates = load_user_templates();
+    let before = templates.len();
+    templates.retain(|t| t.id != id);
+    if templates.len() == before {
+        return Err(format!("Template {} not found", id));
+    }
+    save_user_templates(&templates)?;
+    Ok(format!("Deleted template {}", id))
+}
+
+#[tauri::command]
+pub async fn create_project(
+    app: AppHandle,
+    template: String,
+    name: String,
+    parent: String,
+    options: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let tpl = find_template(&template)?;
+    validate_name(&name)?;
+
+    let parent_dir = PathBuf::from(&parent);
+    if !parent_dir.is_dir() {
+        return Err(format!("Folder not found: {}", parent));
+    }
+    let target = parent_dir.join(&name);
+    if target.exists() {
+        return Err("Target directory already exists".to_string());
+    }
+
+    let mut options = options.unwrap_or_default();
+    for param in &tpl.params {
+        if let Some(default) = &param.default {
+            options.entry(param.key.clone()).or_insert_with(|| default.clone());
+        }
+    }
+
+    let progress = Progress::new(&app, &tpl.id, &name);
+    progress.line(format!("Creating {} from the {} template...", name, tpl.name));
+
+    let created = match &tpl.source {
+        TemplateSource::Composer { package } => from_composer(&progress, package, &name, &parent_dir, &options).await,
+        TemplateSource::Git { url, branch } => from_git(&progress, url, branch.as_deref(), &name, &parent_dir).await,
+        TemplateSource::Archive { url, versioned_url } => {
+            let url = match (options.get("version").filter(|v| !v.is_empty()), versioned_url) {
+                (Some(_), Some(versioned)) => substitute(versioned, &name, &options),
+                _ => substitute(url, &name, &options),
+            };
+            from_archive(&progress, &url, &target).await
+        }
+        TemplateSource::Wordpress => match download_url(options.get("version"), options.get("locale")) {
+            Ok(url) => from_archive(&progress, &url, &target).await,
+            Err(e) => Err(e),
+        },
+        TemplateSource::Local { path } => {
+            progress.line(format!("Copying {}...", path));
+            copy_dir(Path::new(path), &target)
+        }
+    };
+
+    if let Err(e) = created {
+        // Only ever removes a folder this call created; existence was checked above
+        let _ = fs::remove_dir_all(&target);
+        return Err(e);
+    }
+
+    run_template_steps(&progress, &tpl.post_create, &target, &name, &options).await
+        .map_err(|e| format!("Project created at {}, but {}", target.display(), e))?;
+
+    if options.get("skip_pipeline").map(String::as_str) != Some("true") {
+        let ctx = new_project_context(&target, &name, &options);
+        let results = run_pipeline(&app, &ctx, &pipeline_for(&tpl.framework)).await;
+        // The project itself is usable; failed steps can be re-run from the project later
+        for failed in results.iter().filter(|r| r.status == "failed") {
+            progress.line(format!("Step '{}' failed: {}", failed.label, failed.error.clone().unwrap_or_default()));
+        }
+    }
+
+    progress.line(format!("{} setup complete!", tpl.name));
+    Ok(target.to_string_lossy().to_string())
+}
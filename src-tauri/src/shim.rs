@@ -1,24 +1,28 @@
 use std::fs;
-use std::path::PathBuf;
-use std::env;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use crate::filesystem::get_home;
+use crate::filesystem::get_node_path;
 
-fn get_paths() -> Option<(PathBuf, PathBuf)> {
-    #[cfg(target_os = "windows")]
-    let home = env::var("USERPROFILE").ok().map(PathBuf::from)?;
-    #[cfg(not(target_os = "windows"))]
-    let home = env::var("HOME").ok().map(PathBuf::from)?;
+const NODE_TOOLS: [&str; 4] = ["node", "npm", "npx", "corepack"];
 
+fn get_paths() -> Option<(PathBuf, PathBuf)> {
+    let home = get_home()?;
     let services_dir = home.join(".stackmanager").join("services");
     let bin_dir = home.join(".stackmanager").join("bin");
 
     Some((services_dir, bin_dir))
 }
 
+// Node's directory link can't be named "node" on Unix because the node shim lives there
+fn link_name(service: &str) -> String {
+    if service == "node" { "node-current".to_string() } else { service.to_string() }
+}
+
 #[tauri::command]
 pub fn get_active_version(service: String) -> Result<String, String> {
-    let (_, bin_dir) = get_paths().ok_or("Home not found")?;
-    let link_path = bin_dir.join(&service);
+    let (services_dir, bin_dir) = get_paths().ok_or("Home not found")?;
+    let link_path = bin_dir.join(link_name(&service));
 
     if fs::symlink_metadata(&link_path).is_err() {
         return Ok("Not Set".to_string());
@@ -26,6 +30,12 @@ pub fn get_active_version(service: String) -> Result<String, String> {
 
     match fs::read_link(&link_path) {
         Ok(target) => {
+            // Report the installed folder even when the link points at a nested bin dir
+            if let Ok(rel) = target.strip_prefix(&services_dir) {
+                if let Some(first) = rel.components().next() {
+                    return Ok(first.as_os_str().to_string_lossy().to_string());
+                }
+            }
             Ok(target.file_name()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or("Unknown".to_string()))
@@ -34,41 +44,16 @@ pub fn get_active_version(service: String) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-pub fn set_active_version(service: String, version_folder: String) -> Result<String, String> {
-    let (services_dir, bin_dir) = get_paths().ok_or("Could not find home directory")?;
-    
-    let base_target = services_dir.join(&version_folder);
-    let mut target_path = base_target.clone();
-
-    let exe_name = if cfg!(target_os = "windows") { "php.exe" } else { "php" };
-    
-    if !target_path.join(exe_name).exists() {
-        let nested = target_path.join(&version_folder);
-        if nested.join(exe_name).exists() {
-            target_path = nested;
-        }
-    }
-    
-    if !target_path.exists() {
-        return Err(format!("Target version not found at {:?}", target_path));
-    }
-
-    let link_path = bin_dir.join(&service);
-
-    if fs::symlink_metadata(&link_path).is_ok() {
-        #[cfg(target_os = "windows")]
-        {
-            if fs::remove_dir(&link_path).is_err() {
-                if fs::remove_file(&link_path).is_err() {
-                    let _ = Command::new("cmd")
-                        .args(&["/C", "rmdir", "/S", "/Q", &link_path.to_string_lossy()])
-                        .output();
-                }
+fn replace_link(link_path: &Path, target_path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    if fs::symlink_metadata(link_path).is_ok() {
+        if fs::remove_dir(link_path).is_err() {
+            if fs::remove_file(link_path).is_err() {
+                let _ = Command::new("cmd")
+                    .args(&["/C", "rmdir", "/S", "/Q", &link_path.to_string_lossy()])
+                    .output();
             }
         }
-        #[cfg(not(target_os = "windows"))]
-        let _ = fs::remove_file(&link_path);
     }
 
     #[cfg(target_os = "windows")]
@@ -85,11 +70,78 @@ pub fn set_active_version(service: String, version_folder: String) -> Result<Str
         }
     }
 
+    // Build the new link beside the old one and rename it over, so the switch is atomic
     #[cfg(not(target_os = "windows"))]
     {
         use std::os::unix::fs::symlink;
-        symlink(&target_path, &link_path).map_err(|e| format!("Unix Symlink Error: {}", e))?;
+        let tmp_link = link_path.with_file_name(format!(
+            ".{}.tmp",
+            link_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        ));
+        let _ = fs::remove_file(&tmp_link);
+        symlink(target_path, &tmp_link).map_err(|e| format!("Unix Symlink Error: {}", e))?;
+        fs::rename(&tmp_link, link_path).map_err(|e| format!("Unix Symlink Error: {}", e))?;
     }
 
+    Ok(())
+}
+
+// Links bin/node-current to the version and points node/npm/npx/corepack shims through it, like nvm's "current"
+fn set_active_node(bin_dir: &Path, version_folder: &str) -> Result<String, String> {
+    let node_dir = PathBuf::from(get_node_path(version_folder.to_string())?);
+    let current = bin_dir.join(link_name("node"));
+
+    replace_link(&current, &node_dir)?;
+
+    for tool in NODE_TOOLS {
+        #[cfg(target_os = "windows")]
+        {
+            let target = if tool == "node" { "node.exe".to_string() } else { format!("{}.cmd", tool) };
+            let shim = format!("@\"%~dp0{}\\{}\" %*\r\n", link_name("node"), target);
+            fs::write(bin_dir.join(format!("{}.cmd", tool)), shim).map_err(|e| e.to_string())?;
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::symlink;
+            let shim = bin_dir.join(tool);
+            if fs::symlink_metadata(&shim).is_ok() {
+                fs::remove_file(&shim).map_err(|e| e.to_string())?;
+            }
+            // Relative, so the shim follows node-current when the version changes
+            symlink(PathBuf::from(link_name("node")).join(tool), &shim)
+                .map_err(|e| format!("Unix Symlink Error: {}", e))?;
+        }
+    }
+
+    Ok(format!("Global node set to {}", version_folder))
+}
+
+#[tauri::command]
+pub fn set_active_version(service: String, version_folder: String) -> Result<String, String> {
+    let (services_dir, bin_dir) = get_paths().ok_or("Could not find home directory")?;
+
+    if service == "node" {
+        return set_active_node(&bin_dir, &version_folder);
+    }
+    
+    let base_target = services_dir.join(&version_folder);
+    let mut target_path = base_target.clone();
+
+    let exe_name = if cfg!(target_os = "windows") { "php.exe" } else { "php" };
+    
+    if !target_path.join(exe_name).exists() {
+        let nested = target_path.join(&version_folder);
+        if nested.join(exe_name).exists() {
+            target_path = nested;
+        }
+    }
+    
+    if !target_path.exists() {
+        return Err(format!("Target version not found at {:?}", target_path));
+    }
+
+    replace_link(&bin_dir.join(&service), &target_path)?;
+
     Ok(format!("Global {} set to {}", service, version_folder))
 }
\ No newline at end of file
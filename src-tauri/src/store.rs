@@ -1,14 +1,10 @@
 use std::fs;
 use std::path::PathBuf;
-use std::env;
 use tauri::command;
+use crate::filesystem::get_home;
 
 fn get_store_path() -> Option<PathBuf> {
-    #[cfg(target_os = "windows")]
-    let home = env::var("USERPROFILE").ok().map(PathBuf::from)?;
-    #[cfg(not(target_os = "windows"))]
-    let home = env::var("HOME").ok().map(PathBuf::from)?;
-
+    let home = get_home()?;
     Some(home.join(".stackmanager").join("projects.json"))
 }
 
@@ -29,4 +25,15 @@ pub fn load_projects() -> Result<String, String> {
 
     let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
     Ok(data)
-}
\ No newline at end of file
+}
+
+// Projects are owned by the frontend; keep them as raw JSON so unknown fields survive a round trip
+pub fn read_projects() -> Result<Vec<serde_json::Value>, String> {
+    let data = load_projects()?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid projects.json: {}", e))
+}
+
+pub fn write_projects(projects: &[serde_json::Value]) -> Result<(), String> {
+    let data = serde_json::to_string(projects).map_err(|e| e.to_string())?;
+    save_projects(data).map(|_| ())
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, State};
+use crate::cache;
+use crate::filesystem::get_home;
+use crate::frameworks::detect;
+use crate::pipelines::{context_for, tool_dirs, PipelineContext};
+use crate::process_manager::{command_for, launch_supervised, stop_service_by_id, LaunchSpec, ServiceState};
+use crate::scaffold::{composer_phar, php_binary, stream_command};
+use crate::wordpress::ensure_wp_cli;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskCommand {
+    Php { args: Vec<String> },
+    Composer { args: Vec<String> },
+    Npm { args: Vec<String> },
+    // WP-CLI with the project's --path
+    Wp { args: Vec<String> },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectTask {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub command: TaskCommand,
+    // Workers run in the background under the process manager; other tasks run once and finish
+    #[serde(default)]
+    pub worker: bool,
+    // Workers that exit on their own are started again
+    #[serde(default = "default_restart")]
+    pub restart: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskStatus {
+    #[serde(flatten)]
+    pub task: ProjectTask,
+    pub running: bool,
+    pub pid: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskOutput {
+    pub project: String,
+    pub task: String,
+    pub line: String,
+}
+
+fn default_restart() -> bool {
+    true
+}
+
+fn tasks_path() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    Ok(home.join(".stackmanager").join("tasks.json"))
+}
+
+fn project_key(project_path: &str) -> String {
+    project_path.trim_end_matches(['/', '\\']).to_string()
+}
+
+// Saved tasks keyed by project path
+fn load_tasks() -> HashMap<String, Vec<ProjectTask>> {
+    tasks_path().ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_tasks(tasks: &HashMap<String, Vec<ProjectTask>>) -> Result<(), String> {
+    let path = tasks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(tasks).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+fn find_task(project_path: &str, task_id: &str) -> Result<ProjectTask, String> {
+    load_tasks().remove(&project_key(project_path))
+        .and_then(|tasks| tasks.into_iter().find(|t| t.id == task_id))
+        .or_else(|| suggested_tasks(project_path).into_iter().find(|t| t.id == task_id))
+        .ok_or(format!("Task {} not found", task_id))
+}
+
+// Service id for a worker; hashed so paths with any characters make a stable, short id
+fn service_id(project_path: &str, task_id: &str) -> String {
+    let digest = Sha256::digest(project_key(project_path).as_bytes());
+    format!("task_{}_{}", &cache::to_hex(&digest)[..12], task_id)
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|a| a.to_string()).collect()
+}
+
+fn task(id: &str, name: &str, command: TaskCommand, worker: bool) -> ProjectTask {
+    ProjectTask { id: id.into(), name: name.into(), command, worker, restart: true }
+}
+
+pub fn default_tasks(framework: &str, has_package_json: bool) -> Vec<ProjectTask> {
+    let mut tasks = match framework {
+        "laravel" | "statamic" => vec![
+            task("queue-work", "Queue worker", TaskCommand::Php { args: args(&["artisan", "queue:work"]) }, true),
+            task("schedule-work", "Scheduler", TaskCommand::Php { args: args(&["artisan", "schedule:work"]) }, true),
+            task("migrate", "Run migrations", TaskCommand::Php { args: args(&["artisan", "migrate", "--force"]) }, false),
+            task("optimize-clear", "Clear caches", TaskCommand::Php { args: args(&["artisan", "optimize:clear"]) }, false),
+        ],
+        "symfony" => vec![
+            task("messenger-consume", "Messenger consumer", TaskCommand::Php { args: args(&["bin/console", "messenger:consume", "async", "-vv"]) }, true),
+            task("migrate", "Run migrations", TaskCommand::Php { args: args(&["bin/console", "doctrine:migrations:migrate", "--no-interaction"]) }, false),
+            task("cache-clear", "Clear cache", TaskCommand::Php { args: args(&["bin/console", "cache:clear"]) }, false),
+        ],
+        "drupal" => vec![
+            task("cache-rebuild", "Rebuild caches", TaskCommand::Php { args: args(&["vendor/bin/drush", "cache:rebuild"]) }, false),
+            task("cron", "Run cron", TaskCommand::Php { args: args(&["vendor/bin/drush", "cron"]) }, false),
+        ],
+        "codeigniter" => vec![
+            task("migrate", "Run migrations", TaskCommand::Php { args: args(&["spark", "migrate"]) }, false),
+            task("cache-clear", "Clear cache", TaskCommand::Php { args: args(&["spark", "cache:clear"]) }, false),
+        ],
+        "cakephp" => vec![
+            task("migrate", "Run migrations", TaskCommand::Php { args: args(&["bin/cake.php", "migrations", "migrate"]) }, false),
+            task("cache-clear", "Clear caches", TaskCommand::Php { args: args(&["bin/cake.php", "cache", "clear_all"]) }, false),
+        ],
+        "wordpress" => vec![
+            task("cron", "Run due cron events", TaskCommand::Wp { args: args(&["cron", "event", "run", "--due-now"]) }, false),
+            task("cache-flush", "Flush object cache", TaskCommand::Wp { args: args(&["cache", "flush"]) }, false),
+        ],
+        _ => vec![],
+    };
+
+    if has_package_json {
+        tasks.push(task("npm-dev", "Dev server (npm run dev)", TaskCommand::Npm { args: args(&["run", "dev"]) }, true));
+        tasks.push(task("npm-build", "Build assets (npm run build)", TaskCommand::Npm { args: args(&["run", "build"]) }, false));
+    }
+    tasks
+}
+
+// Defaults for the project's framework, as registered or as detected on disk
+pub fn suggested_tasks(project_path: &str) -> Vec<ProjectTask> {
+    let (ctx, framework) = context_for(project_path);
+    let framework = framework.unwrap_or_else(|| detect(&ctx.path).framework);
+    default_tasks(&framework, ctx.path.join("package.json").exists())
+}
+
+// Resolves a task to the project's PHP/Node, with both on PATH for anything it shells out to
+async fn launch_spec(command: &TaskCommand, ctx: &PipelineContext) -> Result<LaunchSpec, String> {
+    let php = || php_binary(ctx.php_version.as_deref()).map(|p| p.to_string_lossy().to_string());
+    let dirs = tool_dirs(ctx)?;
+
+    let (bin_path, args) = match command {
+        TaskCommand::Php { args } => (php()?, args.clone()),
+        TaskCommand::Composer { args } => {
+            let mut full = vec![composer_phar()?.to_string_lossy().to_string(), "--no-interaction".to_string()];
+            full.extend(args.iter().cloned());
+            (php()?, full)
+        }
+        TaskCommand::Wp { args } => {
+            let mut full = vec![
+                ensure_wp_cli().await?.to_string_lossy().to_string(),
+                format!("--path={}", ctx.path.to_string_lossy()),
+            ];
+            full.extend(args.iter().cloned());
+            (php()?, full)
+        }
+        TaskCommand::Npm { args } => {
+            let npm = if cfg!(target_os = "windows") { "npm.cmd" } else { "npm" };
+            // The first tool dir is the project's Node when it has one pinned
+            let bin = dirs.iter().map(|d| d.join(npm)).find(|p| p.exists())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| npm.to_string());
+            (bin, args.clone())
+        }
+        TaskCommand::Command { program, args } => (program.clone(), args.clone()),
+    };
+
+    Ok(LaunchSpec {
+        bin_path,
+        args,
+        cwd: Some(ctx.path.to_string_lossy().to_string()),
+        env_paths: Some(dirs.iter().map(|d| d.to_string_lossy().to_string()).collect()),
+        port: None,
+    })
+}
+
+#[tauri::command]
+pub fn list_project_tasks(state: State<ServiceState>, project_path: String) -> Result<Vec<TaskStatus>, String> {
+    let pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
+    let tasks = load_tasks().remove(&project_key(&project_path)).unwrap_or_default();
+    Ok(tasks.into_iter().map(|task| {
+        let pid = pids.get(&service_id(&project_path, &task.id)).copied();
+        TaskStatus { task, running: pid.is_some(), pid }
+    }).collect())
+}
+
+// Default tasks for the project's framework that haven't been saved yet
+#[tauri::command]
+pub fn suggest_project_tasks(project_path: String) -> Vec<ProjectTask> {
+    let saved = load_tasks().remove(&project_key(&project_path)).unwrap_or_default();
+    suggested_tasks(&project_path).into_iter()
+        .filter(|t| !saved.iter().any(|s| s.id == t.id))
+        .collect()
+}
+
+// Adds the task, or replaces the saved one with the same id
+#[tauri::command]
+pub fn save_project_task(project_path: String, task: ProjectTask) -> Result<String, String> {
+    if task.id.is_empty() || !task.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Task id may only contain letters, numbers, '-' and '_'".to_string());
+    }
+    if task.name.trim().is_empty() {
+        return Err("Task name is required".to_string());
+    }
+
+    let mut all = load_tasks();
+    let tasks = all.entry(project_key(&project_path)).or_default();
+    match tasks.iter_mut().find(|t| t.id == task.id) {
+        Some(existing) => *existing = task.clone(),
+        None => tasks.push(task.clone()),
+    }
+    save_tasks(&all)?;
+    Ok(format!("Saved task {}", task.name))
+}
+
+#[tauri::command]
+pub fn delete_project_task(state: State<ServiceState>, project_path: String, task_id: String) -> Result<String, String> {
+    let _ = stop_service_by_id(&state, &service_id(&project_path, &task_id));
+
+    let mut all = load_tasks();
+    let key = project_key(&project_path);
+    if let Some(tasks) = all.get_mut(&key) {
+        tasks.retain(|t| t.id != task_id);
+        if tasks.is_empty() {
+            all.remove(&key);
+        }
+    }
+    save_tasks(&all)?;
+    Ok(format!("Deleted task {}", task_id))
+}
+
+// Output of both one-shot tasks and workers arrives as "task-output" events
+#[tauri::command]
+pub async fn run_project_task(app: AppHandle, project_path: String, task_id: String) -> Result<String, String> {
+    let task = find_task(&project_path, &task_id)?;
+    let (ctx, _) = context_for(&project_path);
+    if !ctx.path.is_dir() {
+        return Err(format!("Project folder not found: {}", project_path));
+    }
+    let spec = launch_spec(&task.command, &ctx).await?;
+
+    let emitter = app.clone();
+    let project = project_key(&project_path);
+    let id = task.id.clone();
+    let on_line = move |line: String| {
+        let _ = emitter.emit("task-output", TaskOutput { project: project.clone(), task: id.clone(), line });
+    };
+
+    if task.worker {
+        println!("Starting worker {} in {}", task.id, project_path);
+        return launch_supervised(&app, service_id(&project_path, &task.id), spec, task.restart, on_line);
+    }
+
+    println!("Running task {} in {}", task.id, project_path);
+    stream_command(command_for(&spec), on_line).await?;
+    Ok(format!("{} finished", task.name))
+}
+
+#[tauri::command]
+pub fn stop_project_task(state: State<ServiceState>, project_path: String, task_id: String) -> Result<String, String> {
+    stop_service_by_id(&state, &service_id(&project_path, &task_id))
+}
@@ -0,0 +1,235 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::Value;
+use tauri::State;
+use crate::downloader::{download_node, download_php_robust, resolve_node_version};
+use crate::filesystem::get_home;
+use crate::network;
+use crate::process_manager::{kill_pid, ServiceState};
+use crate::shim::set_active_version;
+use crate::store::{read_projects, write_projects};
+
+const PROJECT_VERSION_FIELDS: [&str; 2] = ["phpVersion", "nodeVersion"];
+
+fn get_paths() -> Option<(PathBuf, PathBuf)> {
+    let home = get_home()?;
+    let services_dir = home.join(".stackmanager").join("services");
+    let bin_dir = home.join(".stackmanager").join("bin");
+
+    Some((services_dir, bin_dir))
+}
+
+// Global links in bin/ that resolve into the given service folder, as service names
+fn active_links_to(bin_dir: &Path, version_dir: &Path) -> Vec<String> {
+    let mut services = Vec::new();
+    if let Ok(entries) = fs::read_dir(bin_dir) {
+        for entry in entries.flatten() {
+            if let Ok(target) = fs::read_link(entry.path()) {
+                if target.starts_with(version_dir) {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    services.push(name.trim_end_matches("-current").to_string());
+                }
+            }
+        }
+    }
+    services
+}
+
+fn projects_using(projects: &[Value], folder_name: &str) -> Vec<String> {
+    projects.iter()
+        .filter(|p| PROJECT_VERSION_FIELDS.iter().any(|f| p.get(f).and_then(Value::as_str) == Some(folder_name)))
+        .map(|p| p.get("name").and_then(Value::as_str).unwrap_or("unnamed").to_string())
+        .collect()
+}
+
+fn running_services_using(state: &ServiceState, version_dir: &Path) -> Vec<String> {
+    let launches = match state.launches.lock() {
+        Ok(l) => l,
+        Err(_) => return vec![],
+    };
+    launches.iter()
+        .filter(|(_, spec)| Path::new(&spec.bin_path).starts_with(version_dir))
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+fn repoint_projects(projects: &mut [Value], from: &str, to: &str) -> usize {
+    let mut changed = 0;
+    for project in projects.iter_mut() {
+        for field in PROJECT_VERSION_FIELDS {
+            if project.get(field).and_then(Value::as_str) == Some(from) {
+                project[field] = Value::String(to.to_string());
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+#[tauri::command]
+pub fn uninstall_service(
+    state: State<'_, ServiceState>,
+    folder_name: String,
+    replace_with: Option<String>,
+    stop_running: Option<bool>,
+) -> Result<String, String> {
+    let (services_dir, bin_dir) = get_paths().ok_or("Could not find home directory")?;
+    let version_dir = services_dir.join(&folder_name);
+
+    if folder_name.contains(['/', '\\']) || folder_name.starts_with('.') { return Err("Invalid path".to_string()); }
+    if !version_dir.exists() { return Err("Folder does not exist".to_string()); }
+
+    if let Some(replacement) = &replace_with {
+        if replacement == &folder_name || !services_dir.join(replacement).exists() {
+            return Err(format!("Replacement version {} is not installed", replacement));
+        }
+    }
+
+    let running = running_services_using(&state, &version_dir);
+    let active = active_links_to(&bin_dir, &version_dir);
+    let mut projects = read_projects()?;
+    let referencing = projects_using(&projects, &folder_name);
+
+    if !running.is_empty() && !stop_running.unwrap_or(false) {
+        return Err(format!("{} is used by running services: {}. Stop them first or pass stopRunning.", folder_name, running.join(", ")));
+    }
+
+    if (!active.is_empty() || !referencing.is_empty()) && replace_with.is_none() {
+        let mut reasons = Vec::new();
+        if !active.is_empty() { reasons.push(format!("global {} version", active.join("/"))); }
+        if !referencing.is_empty() { reasons.push(format!("projects: {}", referencing.join(", "))); }
+        return Err(format!("{} is still in use ({}). Choose a replacement version.", folder_name, reasons.join("; ")));
+    }
+
+    if !running.is_empty() {
+        let mut pids = state.pids.lock().map_err(|_| "Failed to lock state")?;
+        let mut launches = state.launches.lock().map_err(|_| "Failed to lock state")?;
+        for id in &running {
+            if let Some(pid) = pids.remove(id) {
+                println!("Stopping {} (PID: {}) before uninstall", id, pid);
+                kill_pid(pid);
+            }
+            launches.remove(id);
+        }
+    }
+
+    if let Some(replacement) = &replace_with {
+        for service in &active {
+            set_active_version(service.clone(), replacement.clone())?;
+        }
+        if repoint_projects(&mut projects, &folder_name, replacement) > 0 {
+            write_projects(&projects)?;
+        }
+    }
+
+    fs::remove_dir_all(&version_dir).map_err(|e| e.to_string())?;
+    Ok(format!("Uninstalled {}", folder_name))
+}
+
+// Directory holding php.exe, which may be nested one level inside the service folder
+fn php_dir(services_dir: &Path, folder_name: &str) -> PathBuf {
+    let base = services_dir.join(folder_name);
+    let exe_name = if cfg!(target_os = "windows") { "php.exe" } else { "php" };
+    let nested = base.join(folder_name);
+    if !base.join(exe_name).exists() && nested.join(exe_name).exists() { nested } else { base }
+}
+
+// "php-8.3.2-Win32-vs16-x64" -> (8, 3, 2); "node-v20.11.0-win-x64" -> (20, 11, 0)
+pub fn parse_folder_version(folder_name: &str, prefix: &str) -> Option<(u32, u32, u32)> {
+    let rest = folder_name.strip_prefix(prefix)?;
+    let version = rest.split('-').next()?;
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+// releases.json maps each supported line ("8.3") to its latest build
+async fn fetch_php_releases() -> Result<Value, String> {
+    let client = network::build_client()?;
+    let url = network::rewrite_url("https://windows.php.net/downloads/releases/releases.json");
+    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() { return Err(format!("Failed to fetch PHP releases: {}", res.status())); }
+
+    let body = res.text().await.map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+// Latest patch of every supported PHP line, e.g. ["8.4.1", "8.3.14", ...]
+pub async fn php_release_versions() -> Result<Vec<String>, String> {
+    let releases = fetch_php_releases().await?;
+    Ok(releases.as_object()
+        .map(|lines| lines.values().filter_map(|r| r.get("version").and_then(Value::as_str).map(str::to_string)).collect())
+        .unwrap_or_default())
+}
+
+async fn latest_php_patch(major: u32, minor: u32) -> Result<String, String> {
+    let releases = fetch_php_releases().await?;
+
+    releases.get(format!("{}.{}", major, minor))
+        .and_then(|r| r.get("version"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or(format!("PHP {}.{} is no longer listed as a supported release line", major, minor))
+}
+
+// Keeps the user's php.ini and any extension DLLs the new build doesn't ship (PECL installs)
+fn carry_over_php_config(old_dir: &Path, new_dir: &Path) -> Result<(), String> {
+    let old_ini = old_dir.join("php.ini");
+    if old_ini.exists() {
+        fs::copy(&old_ini, new_dir.join("php.ini")).map_err(|e| format!("Failed to copy php.ini: {}", e))?;
+    }
+
+    let (old_ext, new_ext) = (old_dir.join("ext"), new_dir.join("ext"));
+    if let Ok(entries) = fs::read_dir(&old_ext) {
+        if !new_ext.exists() { fs::create_dir_all(&new_ext).map_err(|e| e.to_string())?; }
+        for entry in entries.flatten() {
+            let target = new_ext.join(entry.file_name());
+            if entry.path().is_file() && !target.exists() {
+                fs::copy(entry.path(), &target).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn upgrade_service(name: String) -> Result<String, String> {
+    let (services_dir, bin_dir) = get_paths().ok_or("Could not find home directory")?;
+    let old_dir = services_dir.join(&name);
+    if !old_dir.exists() { return Err(format!("{} is not installed", name)); }
+
+    let new_folder = if let Some((major, minor, patch)) = parse_folder_version(&name, "php-") {
+        if !cfg!(target_os = "windows") {
+            return Err("PHP upgrades are only available for Windows builds".to_string());
+        }
+        let latest = latest_php_patch(major, minor).await?;
+        if latest == format!("{}.{}.{}", major, minor, patch) {
+            return Ok(format!("{} is already the latest {}.{} release", name, major, minor));
+        }
+
+        let new_folder = download_php_robust(latest).await?;
+        carry_over_php_config(&php_dir(&services_dir, &name), &php_dir(&services_dir, &new_folder))?;
+        new_folder
+    } else if let Some((major, minor, patch)) = parse_folder_version(&name, "node-v") {
+        let client = network::build_client()?;
+        let latest = resolve_node_version(&client, &format!("{}.{}", major, minor)).await?;
+        if latest == format!("{}.{}.{}", major, minor, patch) {
+            return Ok(format!("{} is already the latest {}.{} release", name, major, minor));
+        }
+
+        download_node(latest).await?
+    } else {
+        return Err(format!("Don't know how to upgrade {}. Only PHP and Node.js lines are supported.", name));
+    };
+
+    for service in active_links_to(&bin_dir, &old_dir) {
+        set_active_version(service, new_folder.clone())?;
+    }
+
+    let mut projects = read_projects()?;
+    if repoint_projects(&mut projects, &name, &new_folder) > 0 {
+        write_projects(&projects)?;
+    }
+
+    Ok(new_folder)
+}
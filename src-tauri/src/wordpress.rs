@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use rand::Rng;
+use crate::credentials::generate_password;
+use crate::db_provision::{existing_project_database, provision_project_database};
+use crate::downloader::fetch_to;
+use crate::filesystem::get_home;
+use crate::pipelines::{context_for, prepend_path, project_url, tool_dirs, PipelineContext};
+use crate::scaffold::{php_binary, stream_command, stream_command_with_input};
+
+const WP_CLI_URL: &str = "https://raw.githubusercontent.com/wp-cli/builds/gh-pages/phar/wp-cli.phar";
+const SALT_KEYS: usize = 8;
+
+fn option<'a>(ctx: &'a PipelineContext, key: &str) -> Option<&'a str> {
+    ctx.options.get(key).map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+// English builds live on wordpress.org, localized ones under downloads.wordpress.org/release/<locale>/
+pub fn download_url(version: Option<&String>, locale: Option<&String>) -> Result<String, String> {
+    let version = version.map(|v| v.trim()).filter(|v| !v.is_empty() && *v != "latest");
+    let locale = locale.map(|l| l.trim()).filter(|l| !l.is_empty() && *l != "en_US");
+
+    if let Some(v) = version {
+        if !v.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+            return Err(format!("Invalid WordPress version: {}", v));
+        }
+    }
+    if let Some(l) = locale {
+        if !l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid locale: {}", l));
+        }
+    }
+
+    Ok(match (version, locale) {
+        (None, None) => "https://wordpress.org/latest.zip".to_string(),
+        (Some(v), None) => format!("https://wordpress.org/wordpress-{}.zip", v),
+        (None, Some(l)) => format!("https://downloads.wordpress.org/release/{}/latest.zip", l),
+        (Some(v), Some(l)) => format!("https://downloads.wordpress.org/release/{}/wordpress-{}.zip", l, v),
+    })
+}
+
+// Same alphabet as api.wordpress.org/secret-key, minus the quote and backslash
+fn generate_salt() -> String {
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_ []{}<>~`+=,.;:/?|";
+    let mut rng = rand::thread_rng();
+    (0..64).map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char).collect()
+}
+
+fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// Builds wp-config.php from the sample that ships with every release
+pub async fn write_wp_config(ctx: &PipelineContext) -> Result<String, String> {
+    let config = ctx.path.join("wp-config.php");
+    if config.exists() {
+        return Ok("wp-config.php already exists; left unchanged".to_string());
+    }
+    let sample = fs::read_to_string(ctx.path.join("wp-config-sample.php"))
+        .map_err(|e| format!("Could not read wp-config-sample.php: {}", e))?;
+
+    let project_path = ctx.path.to_string_lossy().to_string();
+    let db = match existing_project_database(&project_path, Some("mysql")) {
+        Some(existing) => existing,
+        None => provision_project_database(&project_path, "mysql", option(ctx, "db_instance")).await?,
+    };
+
+    let mut salts = 0;
+    let lines: Vec<String> = sample.lines().map(|line| {
+        if line.contains("'DB_NAME'") {
+            line.replace("'database_name_here'", &php_string(&db.database))
+        } else if line.contains("'DB_USER'") {
+            line.replace("'username_here'", &php_string(&db.username))
+        } else if line.contains("'DB_PASSWORD'") {
+            line.replace("'password_here'", &php_string(&db.password))
+        } else if line.contains("'DB_HOST'") {
+            line.replace("'localhost'", &php_string(&format!("{}:{}", db.host, db.port)))
+        } else if line.contains("'put your unique phrase here'") {
+            salts += 1;
+            line.replace("'put your unique phrase here'", &php_string(&generate_salt()))
+        } else if line.trim_start().starts_with("$table_prefix") {
+            match option(ctx, "table_prefix") {
+                Some(prefix) => format!("$table_prefix = {};", php_string(prefix)),
+                None => line.to_string(),
+            }
+        } else {
+            line.to_string()
+        }
+    }).collect();
+
+    if salts < SALT_KEYS {
+        return Err("wp-config-sample.php has an unexpected layout; salts were not generated".to_string());
+    }
+    fs::write(&config, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    Ok(format!("Wrote wp-config.php for database {} on {}", db.database, db.instance))
+}
+
+pub async fn ensure_wp_cli() -> Result<PathBuf, String> {
+    let home = get_home().ok_or("Home not found")?;
+    let phar = home.join(".stackmanager").join("bin").join("wp-cli.phar");
+    if phar.exists() {
+        return Ok(phar);
+    }
+
+    println!("Downloading WP-CLI...");
+    fetch_to(WP_CLI_URL, &phar).await
+        .map_err(|e| format!("Failed to download WP-CLI: {}", e))?;
+    Ok(phar)
+}
+
+async fn wp_command(ctx: &PipelineContext, args: &[String]) -> Result<Command, String> {
+    let phar = ensure_wp_cli().await?;
+    let mut cmd = Command::new(php_binary(ctx.php_version.as_deref())?);
+    cmd.arg(phar)
+        .arg(format!("--path={}", ctx.path.to_string_lossy()))
+        .args(args)
+        .current_dir(&ctx.path);
+    prepend_path(&mut cmd, &tool_dirs(ctx)?);
+    Ok(cmd)
+}
+
+async fn is_installed(ctx: &PipelineContext) -> Result<bool, String> {
+    let mut cmd = wp_command(ctx, &["core".into(), "is-installed".into()]).await?;
+    cmd.stdout(Stdio::null()).stderr(Stdio::null());
+    let status = tauri::async_runtime::spawn_blocking(move || cmd.status())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to run WP-CLI: {}", e))?;
+    Ok(status.success())
+}
+
+fn slugs(ctx: &PipelineContext, key: &str) -> Vec<String> {
+    option(ctx, key)
+        .map(|list| list.split([',', ' ']).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+// Installs core unless the site already is, then the plugin and theme lists
+pub async fn install_site<F>(ctx: &PipelineContext, on_line: F) -> Result<(), String>
+where
+    F: Fn(String) + Clone + Send + 'static,
+{
+    let arg = |s: &str| s.to_string();
+
+    if is_installed(ctx).await? {
+        on_line(arg("WordPress is already installed; skipping core install"));
+    } else {
+        let admin_user = option(ctx, "admin_user").unwrap_or("admin").to_string();
+        let admin_email = option(ctx, "admin_email").map(str::to_string)
+            .unwrap_or_else(|| format!("{}@example.test", admin_user));
+        let generated = option(ctx, "admin_password").is_none();
+        let admin_password = option(ctx, "admin_password").map(str::to_string).unwrap_or_else(generate_password);
+
+        let args = vec![
+            arg("core"), arg("install"),
+            format!("--url={}", project_url(ctx)),
+            format!("--title={}", option(ctx, "site_title").unwrap_or(&ctx.name)),
+            format!("--admin_user={}", admin_user),
+            format!("--admin_email={}", admin_email),
+            arg("--skip-email"),
+            // Read from stdin so the password stays out of the process list
+            arg("--prompt=admin_password"),
+        ];
+        let cmd = wp_command(ctx, &args).await?;
+        stream_command_with_input(cmd, Some(format!("{}\n", admin_password)), on_line.clone()).await?;
+
+        if generated {
+            on_line(format!("Admin login: {} / {}", admin_user, admin_password));
+        }
+    }
+
+    let plugins = slugs(ctx, "plugins");
+    if !plugins.is_empty() {
+        let mut args = vec![arg("plugin"), arg("install")];
+        args.extend(plugins);
+        args.push(arg("--activate"));
+        stream_command(wp_command(ctx, &args).await?, on_line.clone()).await?;
+    }
+
+    let themes = slugs(ctx, "themes");
+    if let Some(first) = themes.first().cloned() {
+        let mut args = vec![arg("theme"), arg("install")];
+        args.extend(themes);
+        stream_command(wp_command(ctx, &args).await?, on_line.clone()).await?;
+        stream_command(wp_command(ctx, &[arg("theme"), arg("activate"), first]).await?, on_line).await?;
+    }
+    Ok(())
+}
+
+// Runs any WP-CLI command in a project with its PHP version and returns stdout
+#[tauri::command]
+pub async fn run_wp_cli(project_path: String, args: Vec<String>) -> Result<String, String> {
+    let (ctx, _) = context_for(&project_path);
+    if !ctx.path.join("wp-includes").is_dir() {
+        return Err("Not a WordPress project".to_string());
+    }
+
+    let mut cmd = wp_command(&ctx, &args).await?;
+    let output = tauri::async_runtime::spawn_blocking(move || cmd.output())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to run WP-CLI: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
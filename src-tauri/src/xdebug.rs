@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::State;
+use crate::php_extensions::{extension_file, install_extension_into, is_extension_enabled, set_extension_enabled_in};
+use crate::php_ini::IniFile;
+use crate::process_manager::{restart_service_by_id, ServiceState};
+use crate::project_ini::{load_overrides, save_overrides};
+
+const DEFAULT_CLIENT_HOST: &str = "127.0.0.1";
+const DEFAULT_CLIENT_PORT: u16 = 9003;
+const XDEBUG_KEYS: [&str; 4] = ["xdebug.mode", "xdebug.client_host", "xdebug.client_port", "xdebug.start_with_request"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DebugStatus {
+    pub xdebug_installed: bool,
+    pub enabled: bool,
+    pub mode: Option<String>,
+    pub client_host: String,
+    pub client_port: u16,
+    // An IDE (PhpStorm, VS Code, ...) is accepting DBGp connections on client_host:client_port
+    pub client_listening: bool,
+}
+
+fn global_ini(bin_dir: &Path) -> IniFile {
+    IniFile::load(&bin_dir.join("php.ini")).unwrap_or_default()
+}
+
+fn is_listening(host: &str, port: u16) -> bool {
+    match (host, port).to_socket_addrs() {
+        Ok(mut addrs) => addrs.any(|addr| TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()),
+        Err(_) => false,
+    }
+}
+
+// Restarts the project's server if it is running so the new ini takes effect
+fn restart_if_running(state: &ServiceState, service_id: &Option<String>) -> Result<bool, String> {
+    let id = match service_id {
+        Some(id) => id,
+        None => return Ok(false),
+    };
+    let running = state.pids.lock().map_err(|_| "Failed to lock state")?.contains_key(id);
+    if running {
+        restart_service_by_id(state, id)?;
+    }
+    Ok(running)
+}
+
+#[tauri::command]
+pub async fn enable_debug_mode(
+    state: State<'_, ServiceState>,
+    project_path: String,
+    bin_path_dir: String,
+    service_id: Option<String>,
+    client_host: Option<String>,
+    client_port: Option<u16>,
+    start_with_request: Option<bool>,
+) -> Result<String, String> {
+    let bin_dir = PathBuf::from(&bin_path_dir);
+
+    if !extension_file(&bin_dir, "xdebug").exists() {
+        install_extension_into(&bin_dir, "xdebug", None).await?;
+        // install_extension_into enables it globally; debug mode is scoped to this project
+        let mut global = global_ini(&bin_dir);
+        set_extension_enabled_in(&mut global, "xdebug", false, false);
+        if global.is_modified() { global.save(&bin_dir.join("php.ini"))?; }
+    }
+
+    let host = client_host.unwrap_or_else(|| DEFAULT_CLIENT_HOST.to_string());
+    let port = client_port.unwrap_or(DEFAULT_CLIENT_PORT);
+    // "yes" connects on every request, "trigger" only with XDEBUG_TRIGGER / the browser extension
+    let start = if start_with_request.unwrap_or(true) { "yes" } else { "trigger" };
+
+    let mut overrides = load_overrides(&project_path)?;
+    if !is_extension_enabled(&global_ini(&bin_dir), "xdebug") {
+        set_extension_enabled_in(&mut overrides, "xdebug", true, true);
+    }
+    overrides.set("xdebug.mode", "debug,develop");
+    overrides.set("xdebug.client_host", &host);
+    overrides.set("xdebug.client_port", &port.to_string());
+    overrides.set("xdebug.start_with_request", start);
+    save_overrides(&project_path, &overrides)?;
+
+    let restarted = restart_if_running(&state, &service_id)?;
+
+    Ok(format!(
+        "Debug mode enabled ({}:{}){}",
+        host, port, if restarted { ", server restarted" } else { "" }
+    ))
+}
+
+#[tauri::command]
+pub fn disable_debug_mode(
+    state: State<'_, ServiceState>,
+    project_path: String,
+    service_id: Option<String>,
+) -> Result<String, String> {
+    let mut overrides = load_overrides(&project_path)?;
+    set_extension_enabled_in(&mut overrides, "xdebug", false, false);
+    for key in XDEBUG_KEYS {
+        overrides.unset(key);
+    }
+
+    if !overrides.is_modified() {
+        return Ok("Debug mode is not enabled".to_string());
+    }
+    save_overrides(&project_path, &overrides)?;
+
+    let restarted = restart_if_running(&state, &service_id)?;
+    Ok(format!("Debug mode disabled{}", if restarted { ", server restarted" } else { "" }))
+}
+
+#[tauri::command]
+pub fn get_debug_status(project_path: String, bin_path_dir: String) -> Result<DebugStatus, String> {
+    let bin_dir = PathBuf::from(&bin_path_dir);
+    let overrides = load_overrides(&project_path)?;
+    let global = global_ini(&bin_dir);
+
+    // Project values win over the global php.ini, mirroring PHP_INI_SCAN_DIR load order
+    let lookup = |key: &str| overrides.get(key).or_else(|| global.get(key));
+
+    let loaded = is_extension_enabled(&overrides, "xdebug") || is_extension_enabled(&global, "xdebug");
+    let mode = lookup("xdebug.mode");
+    let client_host = lookup("xdebug.client_host").unwrap_or_else(|| DEFAULT_CLIENT_HOST.to_string());
+    let client_port = lookup("xdebug.client_port")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_CLIENT_PORT);
+
+    Ok(DebugStatus {
+        xdebug_installed: extension_file(&bin_dir, "xdebug").exists(),
+        enabled: loaded && mode.as_deref().map(|m| m.contains("debug")).unwrap_or(false),
+        client_listening: is_listening(&client_host, client_port),
+        mode,
+        client_host,
+        client_port,
+    })
+}
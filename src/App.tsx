@@ -13,11 +13,44 @@ import { ServiceAPI } from './api/serviceControl';
 
 type ServiceStatus = 'running' | 'stopped' | 'error' | 'starting';
 
+type Framework = 'laravel' | 'statamic' | 'symfony' | 'wordpress' | 'drupal' | 'codeigniter' | 'cakephp' | 'custom'
+  | 'svelte' | 'react' | 'vite' | 'next' | 'nuxt' | 'astro' | 'node';
+
+const PHP_FRAMEWORKS: Framework[] = ['laravel', 'statamic', 'symfony', 'wordpress', 'drupal', 'codeigniter', 'cakephp', 'custom'];
+
+interface FrameworkInfo {
+  framework: Framework;
+  version: string | null;
+  php: string | null;
+  node: string | null;
+  package_manager: string | null;
+  start_command: string | null;
+  docroot: string | null;
+}
+
+interface RuntimeResolution {
+  runtime: 'php' | 'node';
+  requirements: { source: string; constraint: string }[];
+  current: string | null;
+  current_satisfies: boolean;
+  selected: string | null;
+  download: string | null;
+  message: string;
+}
+
+interface ProjectRuntimes {
+  php: RuntimeResolution | null;
+  node: RuntimeResolution | null;
+}
+
 interface Project {
   id: string;
   name: string;
   path: string;
-  framework: 'laravel' | 'symfony' | 'wordpress' | 'custom' | 'svelte' | 'react' | 'node';
+  framework: Framework;
+  frameworkVersion?: string;
+  docroot?: string;
+  packageManager?: string;
   domain: string;
   port: number;
   status: ServiceStatus;
@@ -25,6 +58,13 @@ interface Project {
   nodeVersion?: string;
 }
 
+interface ProjectProgress {
+  id: string;
+  template: string;
+  project: string;
+  line: string;
+}
+
 const StatusIndicator = ({ status }: { status: ServiceStatus }) => {
   switch (status) {
     case 'running': return <div className="flex items-center gap-1 text-emerald-500 text-sm font-medium"><CheckCircle size={14} /> Running</div>;
@@ -95,6 +135,38 @@ export default function App() {
     checkProjectsStatus(newProjects);
   };
 
+  // Switches to installed PHP/Node versions that satisfy the project's version files and manifests
+  const resolveRuntimes = async (project: Project): Promise<Project> => {
+    let runtimes: ProjectRuntimes;
+    try {
+      runtimes = await invoke<ProjectRuntimes>('resolve_project_runtimes', { projectPath: project.path });
+    } catch (e) {
+      await message(`Could not check the project's PHP/Node requirements: ${e}`, { title: "Version resolution failed", kind: "warning" });
+      return project;
+    }
+
+    const updated = { ...project };
+    for (const r of [runtimes.php, runtimes.node]) {
+      if (!r || r.current_satisfies) continue;
+      const field = r.runtime === 'php' ? 'phpVersion' : 'nodeVersion';
+      if (r.selected) {
+        updated[field] = r.selected;
+        continue;
+      }
+      const wanted = r.requirements.map(q => `${q.source}: ${q.constraint}`).join('\n');
+      if (!r.download) {
+        await message(`${r.message}.\n\n${wanted}`, { title: "Missing runtime", kind: "warning" });
+      } else if (await confirm(`${r.message}.\n\n${wanted}\n\nDownload it now?`, { title: "Missing runtime", kind: "warning" })) {
+        try {
+          updated[field] = await invoke<string>(r.runtime === 'php' ? 'download_php_robust' : 'download_node', { version: r.download });
+        } catch (e) {
+          await message(`Download failed: ${e}`, { title: "Error", kind: "error" });
+        }
+      }
+    }
+    return updated;
+  };
+
   const checkProjectsStatus = async (currentProjects: Project[]) => {
     if (currentProjects.length === 0) return;
     try {
@@ -152,9 +224,7 @@ export default function App() {
       return;
     }
     try {
-      const home = await invoke<string>('get_user_home');
-      const binDir = `${home}\\.stackmanager\\services\\postgresql-16.2\\pgsql\\bin`;
-      await invoke('change_postgres_password', { binPath: binDir, newPass: pgNewPass });
+      await invoke('change_postgres_password', { newPass: pgNewPass });
       await message("Postgres password updated!", { title: "Success", kind: "info" });
       setPgNewPass('');
       setShowPgConfig(false);
@@ -171,17 +241,16 @@ export default function App() {
     } else {
       setPostgresStatus('starting');
       try {
-        await invoke('init_postgresql', { versionFolder: serviceName });
+        const instance = await invoke<{ port: number; data_dir: string }>('init_postgresql', { versionFolder: serviceName });
 
         let home = userHome || await invoke<string>('get_user_home');
         const binDir = `${home}\\.stackmanager\\services\\${serviceName}\\pgsql\\bin`;
-        const dataDir = `${home}\\.stackmanager\\data\\postgresql`;
 
         await ServiceAPI.start({
           id: serviceId,
           binPath: `${binDir}\\postgres.exe`,
-          args: ["-D", dataDir],
-          port: 5432
+          args: ["-D", instance.data_dir],
+          port: instance.port
         });
 
         setPostgresStatus('running');
@@ -328,9 +397,7 @@ export default function App() {
       return;
     }
     try {
-      const folderName = "mariadb-10.11.6-winx64";
-      const binDir = await invoke<string>('get_service_bin_path', { serviceName: folderName });
-      await invoke('change_mariadb_password', { binPath: binDir, oldPass: dbOldPass, newPass: dbNewPass });
+      await invoke('change_mariadb_password', { oldPass: dbOldPass, newPass: dbNewPass });
       await message("Root password updated successfully!", { title: "Success", kind: "info" });
       setDbOldPass('');
       setDbNewPass('');
@@ -394,7 +461,8 @@ export default function App() {
     return paths;
   };
 
-  const toggleProjectService = async (project: Project) => {
+  const toggleProjectService = async (selected: Project) => {
+    let project = selected;
     if (missingPaths[project.path] === false) {
       await message("Folder missing.", { title: "Error", kind: "error" });
       return;
@@ -407,6 +475,12 @@ export default function App() {
 
     try {
       if (newStatus === 'running') {
+        const resolved = await resolveRuntimes(project);
+        if (resolved.phpVersion !== project.phpVersion || resolved.nodeVersion !== project.nodeVersion) {
+          project = resolved;
+          await updateAndSave(projects.map(p => p.id === project.id ? { ...resolved, status: 'starting' } : p));
+        }
+
         if (project.domain && project.domain !== 'localhost') {
           await invoke('register_proxy_route', { domain: project.domain, port: project.port });
         }
@@ -415,7 +489,7 @@ export default function App() {
         let args: string[] = [];
         let envPaths = await getEnvPaths(project);
 
-        if (['laravel', 'symfony', 'wordpress', 'custom'].includes(project.framework)) {
+        if (PHP_FRAMEWORKS.includes(project.framework)) {
           let phpBinDir = `${userHome}\\.stackmanager\\bin\\php`;
           if (project.phpVersion && project.phpVersion !== 'Global') {
             try { phpBinDir = await invoke<string>('get_service_bin_path', { serviceName: project.phpVersion }); } catch (e) { }
@@ -423,11 +497,12 @@ export default function App() {
           binPath = `${phpBinDir}\\php.exe`;
           try { await invoke('prepare_php_ini', { binPathDir: phpBinDir }); await new Promise(r => setTimeout(r, 500)); } catch (e) { }
 
-          if (project.framework === 'laravel') {
+          if (project.framework === 'laravel' || project.framework === 'statamic') {
             args = ["artisan", "serve", "--host=127.0.0.1", `--port=${project.port}`];
           } else {
             let docRoot = project.path;
-            if (project.framework === 'symfony') docRoot = `${project.path}/public`;
+            if (project.docroot && project.docroot !== '.') docRoot = `${project.path}/${project.docroot}`;
+            else if (!project.docroot && project.framework === 'symfony') docRoot = `${project.path}/public`;
             args = ["-S", `127.0.0.1:${project.port}`, "-t", docRoot];
           }
         }
@@ -467,8 +542,8 @@ export default function App() {
       const selected = await open({ directory: true, multiple: false });
       if (selected && typeof selected === 'string') {
         const name = selected.split(/[\\/]/).pop() || "Untitled";
-        const detected = await invoke<string>('detect_framework', { path: selected });
-        if (['svelte', 'react', 'node'].includes(detected)) {
+        const detected = await invoke<FrameworkInfo>('detect_framework', { path: selected });
+        if (['svelte', 'react', 'vite', 'node'].includes(detected.framework)) {
           try {
             await invoke('patch_vite_config', { projectPath: selected });
             await message("Vite config patched for StackManager compatibility.", { kind: 'info' });
@@ -479,10 +554,12 @@ export default function App() {
         const existingPorts = projects.map(p => p.port);
         const nextPort = existingPorts.length > 0 ? Math.max(...existingPorts) + 1 : 8001;
         const newProj: Project = {
-          id: crypto.randomUUID(), name, path: selected, framework: detected as any,
+          id: crypto.randomUUID(), name, path: selected, framework: detected.framework,
+          frameworkVersion: detected.version ?? undefined, docroot: detected.docroot ?? undefined,
+          packageManager: detected.package_manager ?? undefined,
           domain: 'localhost', port: nextPort, status: 'stopped', phpVersion: 'Global', nodeVersion: 'System'
         };
-        updateAndSave([...projects, newProj]);
+        updateAndSave([...projects, await resolveRuntimes(newProj)]);
       }
     } catch (err) { console.error(err); }
   };
@@ -498,9 +575,11 @@ export default function App() {
       setIsInstalling(true);
       setInstallTitle("Installing Laravel...");
       setComposerLogs(["Starting Composer..."]);
-      const unlisten = await listen<string>('composer-progress', (event) => setComposerLogs(prev => [...prev, event.payload]));
-      const newPath = await invoke<string>('create_laravel_project', { projectName, parentFolder });
+      const unlisten = await listen<ProjectProgress>('create-project-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
+      const unlistenSteps = await listen<{ line: string }>('post-create-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
+      const newPath = await invoke<string>('create_project', { template: 'laravel', name: projectName, parent: parentFolder });
       unlisten();
+      unlistenSteps();
       setIsInstalling(false);
 
       const existingPorts = projects.map(p => p.port);
@@ -522,11 +601,13 @@ export default function App() {
       setInstallTitle("Installing WordPress...");
       setComposerLogs(["Starting Download..."]);
 
-      const unlisten = await listen<string>('composer-progress', (event) => setComposerLogs(prev => [...prev, event.payload]));
+      const unlisten = await listen<ProjectProgress>('create-project-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
+      const unlistenSteps = await listen<{ line: string }>('post-create-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
 
-      const newPath = await invoke<string>('create_wordpress_project', { projectName, parentFolder });
+      const newPath = await invoke<string>('create_project', { template: 'wordpress', name: projectName, parent: parentFolder });
 
       unlisten();
+      unlistenSteps();
       setIsInstalling(false);
 
       const existingPorts = projects.map(p => p.port);
@@ -564,9 +645,11 @@ export default function App() {
       setInstallTitle("Installing Symfony...");
       setComposerLogs(["Starting Composer..."]);
 
-      const unlisten = await listen<string>('composer-progress', (event) => setComposerLogs(prev => [...prev, event.payload]));
-      const newPath = await invoke<string>('create_symfony_project', { projectName, parentFolder });
+      const unlisten = await listen<ProjectProgress>('create-project-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
+      const unlistenSteps = await listen<{ line: string }>('post-create-progress', (event) => setComposerLogs(prev => [...prev, event.payload.line]));
+      const newPath = await invoke<string>('create_project', { template: 'symfony', name: projectName, parent: parentFolder });
       unlisten();
+      unlistenSteps();
       setIsInstalling(false);
 
       const existingPorts = projects.map(p => p.port);
@@ -632,7 +715,7 @@ export default function App() {
     const folderName = "mariadb-10.11.6-winx64";
     const serviceId = "global_mysql";
     if (mysqlStatus === 'running') { setMysqlStatus('stopped'); await ServiceAPI.stop(serviceId); }
-    else { setMysqlStatus('starting'); try { await invoke('init_mysql', { versionFolder: folderName }); const binDir = await invoke<string>('get_service_bin_path', { serviceName: folderName }); const dataPath = `${userHome}\\.stackmanager\\data\\mysql`; await ServiceAPI.start({ id: serviceId, binPath: `${binDir}\\mysqld.exe`, args: ["--console", `--datadir=${dataPath}`] }); setMysqlStatus('running'); } catch (e) { setMysqlStatus('error'); } }
+    else { setMysqlStatus('starting'); try { const instance = await invoke<{ port: number; config_file: string }>('init_mysql', { versionFolder: folderName }); const binDir = await invoke<string>('get_service_bin_path', { serviceName: folderName }); await ServiceAPI.start({ id: serviceId, binPath: `${binDir}\\mysqld.exe`, args: [`--defaults-file=${instance.config_file}`, "--console"], port: instance.port }); setMysqlStatus('running'); } catch (e) { setMysqlStatus('error'); } }
   };
 
   const handleReAddFolder = async (project: Project) => {
@@ -870,16 +953,24 @@ export default function App() {
                 <select className="text-sm bg-slate-50 border border-slate-200 rounded px-2 py-1 outline-none focus:border-indigo-500"
                   value={selectedProject.framework}
                   onChange={(e) => {
-                    const updatedProject = { ...selectedProject, framework: e.target.value as any };
+                    const updatedProject = { ...selectedProject, framework: e.target.value as Framework };
                     setSelectedProject(updatedProject);
                     updateAndSave(projects.map(p => p.id === selectedProject.id ? updatedProject : p));
                   }}>
                   <option value="custom">Custom PHP</option>
                   <option value="laravel">Laravel</option>
                   <option value="wordpress">WordPress</option>
+                  <option value="statamic">Statamic</option>
                   <option value="symfony">Symfony</option>
+                  <option value="drupal">Drupal</option>
+                  <option value="codeigniter">CodeIgniter</option>
+                  <option value="cakephp">CakePHP</option>
                   <option value="svelte">Svelte (Vite)</option>
                   <option value="react">React (Vite)</option>
+                  <option value="vite">Vite (Generic)</option>
+                  <option value="next">Next.js</option>
+                  <option value="nuxt">Nuxt</option>
+                  <option value="astro">Astro</option>
                   <option value="node">Node.js (Generic)</option>
                 </select>
               </div>